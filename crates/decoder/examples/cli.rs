@@ -2,19 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    fs::{self, DirEntry, File},
+    fs::{self, File},
     io::{self, BufReader, BufWriter, Write},
     process::ExitCode,
 };
 
 use alloy_primitives::B256;
 use clap::{Parser, Subcommand};
-use firehose_protos::{BlockHeader, EthBlock as Block, SolBlock};
+use firehose_protos::{BlockHeader, EthBlock as Block};
 use flat_files_decoder::{
-    read_blocks_from_reader, stream_blocks, AnyBlock, Compression, DecoderError, Reader,
+    read_blocks_from_reader, read_flat_files_parallel, stream_blocks, AnyBlock, Compression,
+    DecoderError, HeaderRecordWithNumber, Reader,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{error, info, level_filters::LevelFilter, subscriber::set_global_default, trace};
+use tracing::{error, info, level_filters::LevelFilter, subscriber::set_global_default};
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 fn main() -> ExitCode {
@@ -95,18 +96,16 @@ fn run() -> Result<(), DecoderError> {
             let mut writer = BufWriter::new(io::stdout().lock());
 
             for block in blocks {
+                let block = match block {
+                    Ok(block) => block,
+                    Err(DecoderError::VerificationFailed { block_number }) => {
+                        info!("Block verification failed, skipping block {block_number}");
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                };
                 let header_record_with_number = HeaderRecordWithNumber::try_from(&block)?;
-                let header_record_bin = bincode::serde::encode_to_vec::<
-                    _,
-                    bincode::config::Configuration<
-                        bincode::config::LittleEndian,
-                        bincode::config::Varint,
-                        bincode::config::NoLimit,
-                    >,
-                >(
-                    &header_record_with_number,
-                    bincode::config::Configuration::default(),
-                )?;
+                let header_record_bin = header_record_with_number.to_versioned_bytes()?;
 
                 let size = header_record_bin.len() as u32;
                 writer.write_all(&size.to_be_bytes())?;
@@ -162,7 +161,8 @@ fn decode_flat_files(
     // Get blocks depending on file or folder
     let blocks = if metadata.is_dir() {
         info!("Processing directory: {}", input_path);
-        read_flat_files(input_path, compression)
+        let num_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        read_flat_files_parallel(input_path, compression, num_threads)
     } else {
         info!("Processing file: {}", input_path);
         read_flat_file(input_path, compression)
@@ -193,10 +193,6 @@ fn decode_flat_files(
     Ok(blocks)
 }
 
-fn create_read_dir(input_path: &str) -> io::Result<fs::ReadDir> {
-    fs::read_dir(input_path)
-}
-
 fn check_block_against_json(block: &Block, headers_dir: &str) -> Result<(), DecoderError> {
     let header_file_path = format!("{}/{}.json", headers_dir, block.number);
     let header_file = File::open(header_file_path)?;
@@ -213,7 +209,7 @@ fn check_block_against_json(block: &Block, headers_dir: &str) -> Result<(), Deco
 
 fn write_block_to_json(block: &AnyBlock, output: &str) -> Result<(), DecoderError> {
     let block_number = match block {
-        AnyBlock::Evm(eth_block) => eth_block.number,
+        AnyBlock::Evm(eth_block) | AnyBlock::Arb(eth_block) => eth_block.number,
         AnyBlock::Sol(sol_block) => sol_block.block_height.unwrap().block_height,
     };
 
@@ -239,105 +235,6 @@ fn read_flat_file(path: &str, compression: Compression) -> Result<Vec<AnyBlock>,
     Ok(blocks)
 }
 
-/// Dbin file type extension
-const EXTENSION: &str = "dbin";
-
-/// Checks if the file extension is `.dbin`, ignoring a .zst extension.
-fn dir_entry_extension_is_dbin(entry: &DirEntry) -> bool {
-    let path = entry.path();
-    let effective_path = if path.extension().and_then(|e| e.to_str()) == Some("zst") {
-        path.with_extension("")
-    } else {
-        path
-    };
-    effective_path
-        .extension()
-        .is_some_and(|ext| ext == EXTENSION)
-}
-
-fn read_flat_files(path: &str, compression: Compression) -> Result<Vec<AnyBlock>, DecoderError> {
-    let read_dir = create_read_dir(path)?;
-
-    let mut blocks: Vec<AnyBlock> = vec![];
-
-    for path in read_dir {
-        let path = path?;
-
-        if !dir_entry_extension_is_dbin(&path) {
-            continue;
-        }
-
-        trace!("Processing file: {}", path.path().display());
-
-        match read_flat_file(path.path().to_str().unwrap(), compression) {
-            Ok(blocks_vec) => {
-                blocks.extend(blocks_vec);
-            }
-            Err(e) => {
-                return Err(e);
-            }
-        }
-    }
-
-    Ok(blocks)
-}
-
-/// A struct to hold the block hash, block number, and total difficulty of a block.
-#[derive(Serialize, Deserialize)]
-struct HeaderRecordWithNumber {
-    block_hash: Vec<u8>,
-    block_number: u64,
-    total_difficulty: Vec<u8>,
-}
-
-/// Try from an Ethereum Block
-impl TryFrom<&Block> for HeaderRecordWithNumber {
-    type Error = DecoderError;
-
-    fn try_from(block: &Block) -> Result<Self, Self::Error> {
-        Ok(HeaderRecordWithNumber {
-            block_hash: block.hash.clone(),
-            block_number: block.number,
-            total_difficulty: block
-                .header()?
-                .total_difficulty
-                .as_ref()
-                .ok_or(Self::Error::TotalDifficultyInvalid)?
-                .bytes
-                .clone(),
-        })
-    }
-}
-
-/// Try from a Solana Block
-impl TryFrom<&SolBlock> for HeaderRecordWithNumber {
-    type Error = DecoderError;
-
-    fn try_from(block: &SolBlock) -> Result<Self, Self::Error> {
-        Ok(HeaderRecordWithNumber {
-            block_hash: block.blockhash.clone().into(),
-            block_number: block.block_height.unwrap().block_height,
-            // There is no field analogous to `total_difficulty` in Solana Blocks
-            total_difficulty: vec![],
-        })
-    }
-}
-
-/// Try from a Generalized AnyBlock enum
-impl TryFrom<&AnyBlock> for HeaderRecordWithNumber {
-    type Error = DecoderError;
-
-    fn try_from(block: &AnyBlock) -> Result<Self, Self::Error> {
-        match block {
-            AnyBlock::Evm(_) => {
-                let eth_block = block.as_eth_block().ok_or(DecoderError::ConversionError)?;
-                HeaderRecordWithNumber::try_from(eth_block)
-            }
-            AnyBlock::Sol(sol_block) => HeaderRecordWithNumber::try_from(sol_block),
-        }
-    }
-}
-
 /// A struct to hold the receipt and transactions root for an [`Block`].
 /// This struct is used to compare the receipt and transactions roots of a block
 /// with the receipt and transactions roots of another block.
@@ -35,7 +35,8 @@ fn main() {
 
     let blocks: Vec<AnyBlock> = stream_blocks(Reader::Buf(reader), EndBlock::Block(99))
         .unwrap()
-        .collect();
+        .collect::<Result<_, _>>()
+        .unwrap();
 
     assert_eq!(blocks.len(), 100);
     println!("read_blocks.rs done");
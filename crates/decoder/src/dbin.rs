@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::convert::TryFrom;
-use std::io::{self, Read};
+use std::io::{Read, Write};
 
 use crate::error::DecoderError;
 
@@ -67,6 +67,13 @@ impl DbinFile {
         &self.header.content_type
     }
 
+    /// Get the content version of the `.dbin` file, such as `"01"`.
+    ///
+    /// Only version 0 files have a content version, so this is `None` for version 1 files.
+    pub fn content_version(&self) -> Option<&str> {
+        self.header.content_version()
+    }
+
     /// Read and parse a `.dbin` file from a `Read` source.
     pub fn try_from_read<R: Read>(mut read: R) -> Result<Self, DecoderError> {
         let header = DbinHeader::try_from_read(&mut read)?;
@@ -77,27 +84,9 @@ impl DbinFile {
     /// Reads messages from a `Read` source following the Dbin format.
     fn read_messages<R: Read>(read: &mut R) -> Result<DbinMessages, DecoderError> {
         let mut messages = Vec::new();
-
-        loop {
-            let bytes = match read_magic_bytes(read) {
-                Ok(bytes) => bytes,
-                // Break loop gracefully if EOF is reached at the start of a new message.
-                Err(DecoderError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => break,
-                Err(e) => return Err(e),
-            };
-
-            let message_length = u32::from_be_bytes(bytes) as usize;
-
-            match read_message(read, message_length) {
-                Ok(message) => messages.push(message),
-                // Return error if EOF occurs in the middle of a message
-                Err(DecoderError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                    return Err(DecoderError::Io(e))
-                }
-                Err(e) => return Err(e),
-            }
+        while let Some(message) = read_next_message(read)? {
+            messages.push(message);
         }
-
         Ok(messages)
     }
 
@@ -105,6 +94,20 @@ impl DbinFile {
     pub fn version(&self) -> Version {
         self.header.version()
     }
+
+    /// Get the header of the `.dbin` file.
+    pub(crate) fn header(&self) -> &DbinHeader {
+        &self.header
+    }
+
+    /// Write the `.dbin` file to a `Write` sink, using the same version and framing it was read with.
+    pub fn write_to<W: Write>(&self, write: &mut W) -> Result<(), DecoderError> {
+        self.header.write_to(write)?;
+        for message in &self.messages {
+            write_message(write, message)?;
+        }
+        Ok(())
+    }
 }
 
 /// implement iterator for DbinFile so that we can iterate over the messages
@@ -118,12 +121,14 @@ impl IntoIterator for DbinFile {
 }
 
 /// Header of a `.dbin` file, containing metadata such as version, content type, and content version.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct DbinHeader {
     /// File format version, the next single byte after the 4 [`DbinMagicBytes`]
     version: Version,
     /// Content type like 'ETH', 'type.googleapis.com/sf.ethereum.type.v2.Block'
     content_type: String,
+    /// Content version like '01', only present in version 0 headers
+    content_version: Option<String>,
 }
 
 impl DbinHeader {
@@ -137,15 +142,13 @@ impl DbinHeader {
     }
 
     fn read_string_field<R: Read>(read: &mut R, size: usize) -> Result<String, DecoderError> {
-        let mut field_bytes = vec![0; size];
-        read.read_exact(&mut field_bytes)?;
+        let field_bytes = read_exact_bytes(read, size)?;
         String::from_utf8(field_bytes).map_err(DecoderError::from)
     }
 
     /// Reads a single byte as a version or field.
     fn read_version_field<R: Read>(read: &mut R) -> Result<u8, DecoderError> {
-        let mut buf = [0; HEADER_VERSION_SIZE];
-        read.read_exact(&mut buf)?;
+        let buf = read_exact_bytes(read, HEADER_VERSION_SIZE)?;
         Ok(buf[0])
     }
 
@@ -157,6 +160,78 @@ impl DbinHeader {
     pub fn content_type(&self) -> &str {
         &self.content_type
     }
+
+    /// Access content_version field, which is `None` for version 1 headers
+    pub fn content_version(&self) -> Option<&str> {
+        self.content_version.as_deref()
+    }
+
+    /// Writes the magic bytes and header fields, following the framing of the header's version.
+    pub(crate) fn write_to<W: Write>(&self, write: &mut W) -> Result<(), DecoderError> {
+        match self.version {
+            Version::V0 => {
+                let content_version = self
+                    .content_version
+                    .as_deref()
+                    .ok_or(DecoderError::HeaderInvalid)?;
+                if self.content_type.len() != HEADER_CONTENT_TYPE_SIZE
+                    || content_version.len() != HEADER_CONTENT_VERSION_SIZE
+                {
+                    return Err(DecoderError::HeaderInvalid);
+                }
+
+                write.write_all(MAGIC_BYTES)?;
+                write.write_all(&[Version::V0 as u8])?;
+                write.write_all(self.content_type.as_bytes())?;
+                write.write_all(content_version.as_bytes())?;
+                Ok(())
+            }
+            Version::V1 => write_header_v1(write, &self.content_type),
+        }
+    }
+}
+
+/// Merges several `.dbin` streams into a single one, such as per-block files into an era file.
+///
+/// All inputs must share the same version, content type and content version. A single header is
+/// written to `out`, followed by the message frames of every input in order. Nothing is written
+/// when `inputs` is empty.
+pub fn merge_dbin<R: Read, W: Write>(inputs: Vec<R>, out: &mut W) -> Result<(), DecoderError> {
+    let mut merged_header: Option<DbinHeader> = None;
+
+    for mut input in inputs {
+        let header = DbinHeader::try_from_read(&mut input)?;
+        match &merged_header {
+            None => {
+                header.write_to(out)?;
+                merged_header = Some(header);
+            }
+            Some(merged) => {
+                if header.version != merged.version {
+                    return Err(DecoderError::VersionConflict);
+                }
+                if header.content_type != merged.content_type {
+                    return Err(DecoderError::ContentTypeConflict {
+                        expected: merged.content_type.clone(),
+                        found: header.content_type,
+                    });
+                }
+                // Only version 0 headers carry a content version, and both versions match here
+                if header.content_version != merged.content_version {
+                    return Err(DecoderError::ContentVersionConflict {
+                        expected: merged.content_version.clone().unwrap_or_default(),
+                        found: header.content_version.unwrap_or_default(),
+                    });
+                }
+            }
+        }
+
+        while let Some(message) = read_next_message(&mut input)? {
+            write_message(out, &message)?;
+        }
+    }
+
+    Ok(())
 }
 
 fn magic_bytes_valid(bytes: &MagicBytes) -> bool {
@@ -172,54 +247,148 @@ fn read_header<R: Read>(read: &mut R) -> Result<DbinHeader, DecoderError> {
         Version::V0 => HEADER_CONTENT_TYPE_SIZE,
         // For v1, the next two bytes indicate the length of the content type string
         Version::V1 => {
-            let mut field_bytes: [u8; 2] = [0; 2];
-            read.read_exact(&mut field_bytes)?;
-            u16::from_be_bytes(field_bytes) as usize
+            let field_bytes = read_exact_bytes(read, 2)?;
+            u16::from_be_bytes([field_bytes[0], field_bytes[1]]) as usize
         }
     };
 
     let content_type = DbinHeader::read_string_field(read, type_size)?;
 
     // Read content version if V0
-    if matches!(version, Version::V0) {
-        let _ = DbinHeader::read_string_field(read, HEADER_CONTENT_VERSION_SIZE)?;
-    }
+    let content_version = match version {
+        Version::V0 => Some(DbinHeader::read_string_field(
+            read,
+            HEADER_CONTENT_VERSION_SIZE,
+        )?),
+        Version::V1 => None,
+    };
 
     Ok(DbinHeader {
         version,
         content_type,
+        content_version,
     })
 }
 
 fn read_magic_bytes<R: Read>(read: &mut R) -> Result<MagicBytes, DecoderError> {
-    let bytes = read_message(read, PREFIX_SIZE)?;
+    let bytes = read_exact_bytes(read, PREFIX_SIZE)?;
     match bytes.try_into() {
         Ok(magic_bytes) => Ok(magic_bytes),
         Err(_) => Err(DecoderError::MagicBytesInvalid),
     }
 }
 
-/// Reads a single message, assuming the size-prefix format defined by `.dbin`.
-fn read_message<R: Read>(read: &mut R, length: usize) -> Result<DbinMessage, DecoderError> {
-    let mut message = vec![0; length];
-    read.read_exact(&mut message)?;
-    Ok(message)
+/// Reads the next 4-byte prefix, either magic bytes or a message length, returning `None` if
+/// the stream ends cleanly before it.
+///
+/// EOF anywhere after the first byte of the prefix is reported as
+/// [`DecoderError::TruncatedMessage`].
+fn read_next_prefix<R: Read>(read: &mut R) -> Result<Option<MagicBytes>, DecoderError> {
+    let prefix = read_up_to(read, PREFIX_SIZE)?;
+    // EOF at the start of a new message marks the end of the stream.
+    if prefix.is_empty() {
+        return Ok(None);
+    }
+    prefix
+        .try_into()
+        .map(Some)
+        .map_err(|prefix: Vec<u8>| DecoderError::TruncatedMessage {
+            expected: PREFIX_SIZE,
+            got: prefix.len(),
+        })
+}
+
+/// Reads the next length-prefixed message, returning `None` if the stream ends cleanly before it.
+///
+/// EOF anywhere after the first byte of the length prefix is reported as
+/// [`DecoderError::TruncatedMessage`].
+fn read_next_message<R: Read>(read: &mut R) -> Result<Option<DbinMessage>, DecoderError> {
+    match read_next_prefix(read)? {
+        Some(bytes) => read_exact_bytes(read, u32::from_be_bytes(bytes) as usize).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Reads exactly `length` bytes, reporting a stream which ends first as
+/// [`DecoderError::TruncatedMessage`].
+fn read_exact_bytes<R: Read>(read: &mut R, length: usize) -> Result<Vec<u8>, DecoderError> {
+    let bytes = read_up_to(read, length)?;
+    if bytes.len() != length {
+        return Err(DecoderError::TruncatedMessage {
+            expected: length,
+            got: bytes.len(),
+        });
+    }
+    Ok(bytes)
+}
+
+/// Reads `length` bytes, or fewer if the stream ends first.
+fn read_up_to<R: Read>(read: &mut R, length: usize) -> Result<Vec<u8>, DecoderError> {
+    // The length comes from the file, so the buffer only grows with the bytes actually read.
+    let mut bytes = Vec::new();
+    read.take(length as u64).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Writes a version 1 `.dbin` header with the given content type.
+pub(crate) fn write_header_v1<W: Write>(
+    write: &mut W,
+    content_type: &str,
+) -> Result<(), DecoderError> {
+    let content_type_size =
+        u16::try_from(content_type.len()).map_err(|_| DecoderError::HeaderInvalid)?;
+
+    write.write_all(MAGIC_BYTES)?;
+    write.write_all(&[Version::V1 as u8])?;
+    write.write_all(&content_type_size.to_be_bytes())?;
+    write.write_all(content_type.as_bytes())?;
+    Ok(())
+}
+
+/// Writes a single message, using the size-prefix format defined by `.dbin`.
+pub(crate) fn write_message<W: Write>(write: &mut W, message: &[u8]) -> Result<(), DecoderError> {
+    let message_size = u32::try_from(message.len()).map_err(|_| DecoderError::BytesInvalid)?;
+
+    write.write_all(&message_size.to_be_bytes())?;
+    write.write_all(message)?;
+    Ok(())
 }
 
 /// Read the next block from a flat file reader.
 pub fn read_block_from_reader<R: Read>(read: &mut R) -> Result<DbinMessage, DecoderError> {
-    let mut magic_bytes = read_magic_bytes(read)?;
+    read_block_and_header_from_reader(read).map(|(_, message)| message)
+}
+
+/// Read the next block from a flat file reader, together with the header of the `.dbin` file
+/// it starts, if any.
+///
+/// Concatenated `.dbin` files repeat their header before their first block, so the header
+/// is `Some` whenever the block is the first of a new file. Its content type applies to this
+/// block and every following block up to the next header.
+///
+/// A reader which ends cleanly before the next block returns an [`std::io::Error`] of kind
+/// [`std::io::ErrorKind::UnexpectedEof`]. A reader which ends in the middle of a header,
+/// length prefix or message returns [`DecoderError::TruncatedMessage`].
+pub fn read_block_and_header_from_reader<R: Read>(
+    read: &mut R,
+) -> Result<(Option<DbinHeader>, DbinMessage), DecoderError> {
+    let mut magic_bytes = read_next_prefix(read)?.ok_or_else(end_of_stream)?;
+    let mut header = None;
 
     if magic_bytes_valid(&magic_bytes) {
         // Block messages are separated by "dbin" (the magical 4 bytes), so each
         // new occurrence marks the start of a new .dbin file
-        _ = read_header(read)?;
-        magic_bytes = read_magic_bytes(read)?;
+        header = Some(read_header(read)?);
+        magic_bytes = read_next_prefix(read)?.ok_or_else(end_of_stream)?;
     }
 
     let message_size = u32::from_be_bytes(magic_bytes) as usize;
 
-    read_message(read, message_size)
+    Ok((header, read_exact_bytes(read, message_size)?))
+}
+
+fn end_of_stream() -> DecoderError {
+    std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()
 }
 
 #[cfg(test)]
@@ -235,6 +404,7 @@ mod tests {
         let header = DbinHeader::try_from_read(&mut cursor).expect("Failed to parse header");
         assert_eq!(header.version(), Version::V0);
         assert_eq!(header.content_type, "ETH");
+        assert_eq!(header.content_version(), Some("01"));
     }
 
     #[test]
@@ -252,6 +422,7 @@ mod tests {
             header.content_type,
             "type.googleapis.com/sf.solana.type.v1.Block"
         );
+        assert_eq!(header.content_version(), None);
     }
 
     #[test]
@@ -311,15 +482,193 @@ mod tests {
         data.extend_from_slice(&(4u32.to_be_bytes())); // message length
         data.extend_from_slice(b"test");
 
-        // truncate to simulate EOF after header
+        // truncate to simulate EOF in the middle of a message
         let mut cursor = Cursor::new(&data[..data.len() - 2]);
 
         let result = DbinFile::try_from_read(&mut cursor);
-        assert!(
-            matches!(result, Err(DecoderError::Io(ref e)) if e.kind() == io::ErrorKind::UnexpectedEof)
+        assert!(matches!(
+            result,
+            Err(DecoderError::TruncatedMessage {
+                expected: 4,
+                got: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_read_block_and_header_from_truncated_reader() {
+        let mut data = vec![];
+        data.extend_from_slice(&[b'd', b'b', b'i', b'n', 0u8, b'E', b'T', b'H', b'0', b'1']);
+        data.extend_from_slice(&(4u32.to_be_bytes()));
+        data.extend_from_slice(b"test");
+
+        let (header, message) = read_block_and_header_from_reader(&mut data.as_slice()).unwrap();
+        assert_eq!(header.unwrap().content_type(), "ETH");
+        assert_eq!(message, b"test");
+
+        // partial header
+        let result = read_block_and_header_from_reader(&mut &data[..7]);
+        assert!(matches!(
+            result,
+            Err(DecoderError::TruncatedMessage {
+                expected: 3,
+                got: 2
+            })
+        ));
+
+        // partial length prefix
+        let result = read_block_and_header_from_reader(&mut &data[10..13]);
+        assert!(matches!(
+            result,
+            Err(DecoderError::TruncatedMessage {
+                expected: 4,
+                got: 3
+            })
+        ));
+
+        // partial message
+        let result = read_block_and_header_from_reader(&mut &data[10..16]);
+        assert!(matches!(
+            result,
+            Err(DecoderError::TruncatedMessage {
+                expected: 4,
+                got: 2
+            })
+        ));
+
+        // EOF before the next block ends the stream
+        let result = read_block_and_header_from_reader(&mut &data[..0]);
+        assert!(matches!(
+            result,
+            Err(DecoderError::Io(ref e)) if e.kind() == std::io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn test_truncated_length_prefix() {
+        let mut data = vec![];
+        data.extend_from_slice(&[b'd', b'b', b'i', b'n', 0u8, b'E', b'T', b'H', b'0', b'1']);
+        data.extend_from_slice(&(4u32.to_be_bytes())); // message length
+        data.extend_from_slice(b"test");
+        data.extend_from_slice(&(4u32.to_be_bytes())[..3]); // partial message length
+
+        let result = DbinFile::try_from_read(Cursor::new(&data));
+        assert!(matches!(
+            result,
+            Err(DecoderError::TruncatedMessage {
+                expected: 4,
+                got: 3
+            })
+        ));
+
+        // EOF exactly at a message boundary ends the file cleanly
+        let dbin_file = DbinFile::try_from_read(Cursor::new(&data[..data.len() - 3])).unwrap();
+        assert_eq!(dbin_file.messages, vec![b"test".to_vec()]);
+    }
+
+    #[test]
+    fn test_write_to_round_trip_v1() {
+        let mut data = vec![];
+        data.extend_from_slice(&[
+            100, 98, 105, 110, 1, 0, 43, 116, 121, 112, 101, 46, 103, 111, 111, 103, 108, 101, 97,
+            112, 105, 115, 46, 99, 111, 109, 47, 115, 102, 46, 115, 111, 108, 97, 110, 97, 46, 116,
+            121, 112, 101, 46, 118, 49, 46, 66, 108, 111, 99, 107,
+        ]);
+        data.extend_from_slice(&(4u32.to_be_bytes())); // message length
+        data.extend_from_slice(b"test");
+
+        let dbin_file = DbinFile::try_from_read(Cursor::new(&data)).unwrap();
+        let mut written = vec![];
+        dbin_file.write_to(&mut written).unwrap();
+
+        assert_eq!(written, data);
+    }
+
+    #[test]
+    fn test_write_to_round_trip_v0() {
+        let data = std::fs::read("tests/0000000000.dbin").unwrap();
+        let dbin_file = DbinFile::try_from_read(Cursor::new(&data)).unwrap();
+
+        let mut written = vec![];
+        dbin_file.write_to(&mut written).unwrap();
+        assert_eq!(written, data);
+
+        let reparsed = DbinFile::try_from_read(Cursor::new(&written)).unwrap();
+        assert_eq!(reparsed.version(), Version::V0);
+        assert_eq!(reparsed.content_version(), dbin_file.content_version());
+        assert_eq!(reparsed.messages, dbin_file.messages);
+    }
+
+    fn single_message_dbin(header: &DbinHeader, message: &[u8]) -> Vec<u8> {
+        let mut data = vec![];
+        header.write_to(&mut data).unwrap();
+        write_message(&mut data, message).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_merge_dbin() {
+        let data = std::fs::read("tests/0000000000.dbin").unwrap();
+        let dbin_file = DbinFile::try_from_read(Cursor::new(&data)).unwrap();
+        let first = single_message_dbin(&dbin_file.header, &dbin_file.messages[0]);
+        let second = single_message_dbin(&dbin_file.header, &dbin_file.messages[1]);
+
+        let mut merged = vec![];
+        merge_dbin(vec![first.as_slice(), second.as_slice()], &mut merged).unwrap();
+
+        let merged_file = DbinFile::try_from_read(Cursor::new(&merged)).unwrap();
+        assert_eq!(merged_file.version(), Version::V0);
+        assert_eq!(merged_file.content_type(), "ETH");
+        assert_eq!(merged_file.messages, dbin_file.messages[..2]);
+
+        let blocks = crate::read_blocks_from_reader(merged.as_slice(), false.into()).unwrap();
+        let expected = crate::read_blocks_from_reader(data.as_slice(), false.into()).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            blocks[0].as_eth_block().unwrap(),
+            expected[0].as_eth_block().unwrap()
+        );
+        assert_eq!(
+            blocks[1].as_eth_block().unwrap(),
+            expected[1].as_eth_block().unwrap()
         );
     }
 
+    #[test]
+    fn test_merge_dbin_content_type_conflict() {
+        let mut eth = vec![];
+        eth.extend_from_slice(&[b'd', b'b', b'i', b'n', 0u8, b'E', b'T', b'H', b'0', b'1']);
+        let mut sol = vec![];
+        write_header_v1(&mut sol, "type.googleapis.com/sf.solana.type.v1.Block").unwrap();
+        let mut other = vec![];
+        other.extend_from_slice(&[b'd', b'b', b'i', b'n', 0u8, b'S', b'O', b'L', b'0', b'1']);
+
+        let result = merge_dbin(vec![eth.as_slice(), sol.as_slice()], &mut vec![]);
+        assert!(matches!(result, Err(DecoderError::VersionConflict)));
+
+        let result = merge_dbin(vec![eth.as_slice(), other.as_slice()], &mut vec![]);
+        assert!(matches!(
+            result,
+            Err(DecoderError::ContentTypeConflict { ref expected, ref found })
+                if expected == "ETH" && found == "SOL"
+        ));
+    }
+
+    #[test]
+    fn test_merge_dbin_content_version_conflict() {
+        let mut first = vec![];
+        first.extend_from_slice(&[b'd', b'b', b'i', b'n', 0u8, b'E', b'T', b'H', b'0', b'1']);
+        let mut second = vec![];
+        second.extend_from_slice(&[b'd', b'b', b'i', b'n', 0u8, b'E', b'T', b'H', b'0', b'2']);
+
+        let result = merge_dbin(vec![first.as_slice(), second.as_slice()], &mut vec![]);
+        assert!(matches!(
+            result,
+            Err(DecoderError::ContentVersionConflict { ref expected, ref found })
+                if expected == "01" && found == "02"
+        ));
+    }
+
     #[test]
     fn test_iterator_behavior() {
         let mut data = vec![];
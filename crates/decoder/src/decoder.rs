@@ -2,26 +2,38 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
+    collections::BTreeMap,
     fs::File,
-    io::{BufReader, Cursor, Read},
+    io::{BufReader, BufWriter, Cursor, Read, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
 };
 
-use crate::{dbin::read_block_from_reader, error::DecoderError, DbinFile, DbinHeader};
+use crate::{
+    dbin::{read_block_and_header_from_reader, write_message},
+    error::DecoderError,
+    DbinFile, DbinHeader, Version,
+};
 use firehose_protos::{
     BigInt, BlockHeader, BstreamBlock, EthBlock as Block, SolBlock, Timestamp, Uint64NestedArray,
 };
+use flate2::read::GzDecoder;
 use parquet::{
+    data_type::Decimal,
+    errors::ParquetError,
     file::reader::{FileReader, SerializedFileReader},
-    record::RowAccessor,
+    record::{Field, Row, RowAccessor},
 };
 use prost::Message;
-use tracing::{error, info};
+use tracing::{error, info, trace};
 
-/// Work with data compression, including zstd.
+/// Work with data compression, including zstd and gzip.
 #[derive(Clone, Copy, Debug, Default)]
 pub enum Compression {
     /// Zstd compression.
     Zstd,
+    /// Gzip compression.
+    Gzip,
     /// No compression.
     #[default]
     None,
@@ -31,6 +43,7 @@ impl From<&str> for Compression {
     fn from(value: &str) -> Self {
         match value.to_lowercase().as_str() {
             "true" | "1" => Compression::Zstd,
+            "gzip" | "gz" => Compression::Gzip,
             _ => Compression::None,
         }
     }
@@ -53,6 +66,8 @@ pub enum AnyBlock {
     Evm(Block),
     /// Solana Block
     Sol(SolBlock),
+    /// Arbitrum Nitro Block, which shares the EVM Block definition
+    Arb(Block),
 }
 
 impl AnyBlock {
@@ -60,7 +75,7 @@ impl AnyBlock {
     /// a firehose_protos::EthBlock
     pub fn try_into_eth_block(self) -> Result<Block, DecoderError> {
         match self {
-            AnyBlock::Evm(block) => Ok(block),
+            AnyBlock::Evm(block) | AnyBlock::Arb(block) => Ok(block),
             _ => Err(DecoderError::ConversionError),
         }
     }
@@ -77,7 +92,7 @@ impl AnyBlock {
     /// Borrow-based conversion to extract reference to an EthBlock
     pub fn as_eth_block(&self) -> Option<&Block> {
         match self {
-            AnyBlock::Evm(b) => Some(b),
+            AnyBlock::Evm(b) | AnyBlock::Arb(b) => Some(b),
             _ => None,
         }
     }
@@ -90,15 +105,32 @@ impl AnyBlock {
         }
     }
 
-    /// Determine if an AnyBlock instance is an Evm variant
+    /// Determine if an AnyBlock instance holds an EVM Block, i.e. is an Evm or Arb variant,
+    /// matching [`AnyBlock::as_eth_block`] and [`AnyBlock::try_into_eth_block`]
     pub fn is_eth_block(&self) -> bool {
-        matches!(self, AnyBlock::Evm(_))
+        matches!(self, AnyBlock::Evm(_) | AnyBlock::Arb(_))
     }
 
     /// Determine if an AnyBlock instance is a Sol variant
     pub fn is_sol_block(&self) -> bool {
         matches!(self, AnyBlock::Sol(_))
     }
+
+    /// Determine if an AnyBlock instance is an Arb variant
+    pub fn is_arb_block(&self) -> bool {
+        matches!(self, AnyBlock::Arb(_))
+    }
+
+    /// Get the block number, which is the block height for Solana blocks, or their slot if
+    /// the block height is missing
+    pub fn number(&self) -> u64 {
+        match self {
+            AnyBlock::Evm(block) | AnyBlock::Arb(block) => block.number,
+            AnyBlock::Sol(block) => block
+                .block_height
+                .map_or(block.slot, |height| height.block_height),
+        }
+    }
 }
 
 /// The content type (or proto definition type) is a field in the dbin file structure
@@ -107,12 +139,14 @@ impl AnyBlock {
 /// from these enumerated chains, but others may be added in the
 /// future. The content type in the dbin header may also
 /// vary depending on the version of the file.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ContentType {
     /// Indicates EVM Block content.
     Evm,
     /// Indicates Solana Block content.
     Sol,
+    /// Indicates Arbitrum Nitro Block content.
+    Arb,
 }
 
 impl TryFrom<&str> for ContentType {
@@ -124,10 +158,81 @@ impl TryFrom<&str> for ContentType {
         match value {
             "ETH" | "type.googleapis.com/sf.ethereum.type.v2.Block" => Ok(ContentType::Evm),
             "type.googleapis.com/sf.solana.type.v1.Block" => Ok(ContentType::Sol),
+            "type.googleapis.com/sf.arbitrum.type.v1.Block" => Ok(ContentType::Arb),
             _ => Err(DecoderError::ContentTypeInvalid(value.to_string())),
         }
     }
 }
+
+/// Summary of a flat file, as reported by [`identify`].
+#[derive(Clone, Debug)]
+pub struct FileInfo {
+    /// Version of the dbin file format.
+    pub version: Version,
+    /// Content type recorded in the dbin header, such as `"ETH"`.
+    pub content_type: String,
+    /// Chain inferred from the content type, or `None` if the content type is not recognized.
+    pub chain: Option<ContentType>,
+    /// Number of the first block in the file. For Solana blocks this is the slot.
+    /// `None` if the file has no messages or the chain is not recognized.
+    pub first_block_number: Option<u64>,
+    /// Number of messages in the file.
+    pub message_count: usize,
+}
+
+/// Wraps `reader` in the decoder for `compression`, if any.
+fn decompressed<'a, R: Read + 'a>(
+    reader: R,
+    compression: Compression,
+) -> Result<Box<dyn Read + 'a>, DecoderError> {
+    Ok(match compression {
+        Compression::Zstd => Box::new(zstd::stream::Decoder::new(reader)?),
+        Compression::Gzip => Box::new(GzDecoder::new(reader)),
+        Compression::None => Box::new(reader),
+    })
+}
+
+/// Identify the contents of a flat file without verifying its blocks.
+///
+/// Reads the dbin header, counts the messages and decodes only the first one to report
+/// the first block number. Files with a `.zst` extension are treated as Zstd-compressed, and
+/// files with a `.gz` extension as Gzip-compressed.
+pub fn identify<P: AsRef<Path>>(path: P) -> Result<FileInfo, DecoderError> {
+    let path = path.as_ref();
+    let compression = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("zst") => Compression::Zstd,
+        Some("gz") => Compression::Gzip,
+        _ => Compression::None,
+    };
+
+    let reader = BufReader::new(File::open(path)?);
+    let mut file_contents = decompressed(reader, compression)?;
+
+    let dbin_file = DbinFile::try_from_read(&mut file_contents)?;
+    let version = dbin_file.version();
+    let content_type = dbin_file.content_type().to_string();
+    let chain = ContentType::try_from(content_type.as_str()).ok();
+
+    let mut messages = dbin_file.into_iter();
+    let message_count = messages.len();
+
+    let first_block_number = match (&chain, messages.next()) {
+        (Some(chain), Some(message)) => match decode_block_from_bytes(&message, chain.clone())? {
+            AnyBlock::Evm(block) | AnyBlock::Arb(block) => Some(block.number),
+            AnyBlock::Sol(block) => Some(block.slot),
+        },
+        _ => None,
+    };
+
+    Ok(FileInfo {
+        version,
+        content_type,
+        chain,
+        first_block_number,
+        message_count,
+    })
+}
+
 /// Read blocks from a flat file reader.
 ///
 /// This function processes flat files that are already loaded into memory, supporting both
@@ -139,15 +244,67 @@ impl TryFrom<&str> for ContentType {
 ///
 /// * `reader`: A readable source of the file contents, implementing the [`Read`] trait.
 /// * `compression`: The compression type applied to the flat file's data, if any. Accepts [`Compression::Zstd`]
-///   for Zstd-compressed data, or [`Compression::None`] for uncompressed data.
+///   for Zstd-compressed data, [`Compression::Gzip`] for Gzip-compressed data, or [`Compression::None`]
+///   for uncompressed data.
 pub fn read_blocks_from_reader<R: Read>(
     reader: R,
     compression: Compression,
 ) -> Result<Vec<AnyBlock>, DecoderError> {
-    let mut file_contents: Box<dyn Read> = match compression {
-        Compression::Zstd => Box::new(Cursor::new(zstd::decode_all(reader)?)),
-        Compression::None => Box::new(reader),
-    };
+    read_blocks_from_reader_with_options(reader, compression, VerifyOptions::default())
+}
+
+/// Read EVM blocks from a flat file reader.
+///
+/// A thin wrapper around [`read_blocks_from_reader`] for callers that only handle Ethereum
+/// flat files. Returns [`DecoderError::ConversionError`] if the file contains non-EVM blocks.
+pub fn read_eth_blocks_from_reader<R: Read>(
+    reader: R,
+    compression: Compression,
+) -> Result<Vec<Block>, DecoderError> {
+    read_blocks_from_reader(reader, compression)?
+        .into_iter()
+        .map(AnyBlock::try_into_eth_block)
+        .collect()
+}
+
+/// Options controlling which checks are run when verifying decoded blocks.
+///
+/// The default options run the checks performed by [`read_blocks_from_reader`]. Disabling
+/// checks is useful when a file has already been validated, or when decoding test data which
+/// is not expected to verify.
+#[derive(Clone, Copy, Debug)]
+pub struct VerifyOptions {
+    /// Check the receipt root against the receipts in the block. Enabled by default.
+    pub receipt_root: bool,
+    /// Check the transaction root against the transactions in the block. Enabled by default.
+    pub transaction_root: bool,
+    /// Check the block hash against the block header. Enabled by default.
+    pub block_hash: bool,
+    /// Check that the header's gas used equals the cumulative gas used of the last receipt.
+    /// Disabled by default.
+    pub gas_used: bool,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self {
+            receipt_root: true,
+            transaction_root: true,
+            block_hash: true,
+            gas_used: false,
+        }
+    }
+}
+
+/// Read blocks from a flat file reader, verifying them according to `verify_options`.
+///
+/// See [`read_blocks_from_reader`] for details.
+pub fn read_blocks_from_reader_with_options<R: Read>(
+    reader: R,
+    compression: Compression,
+    verify_options: VerifyOptions,
+) -> Result<Vec<AnyBlock>, DecoderError> {
+    let mut file_contents = decompressed(reader, compression)?;
 
     let dbin_file = DbinFile::try_from_read(&mut file_contents)?;
     let content_type: ContentType = dbin_file.content_type().try_into()?;
@@ -156,24 +313,394 @@ pub fn read_blocks_from_reader<R: Read>(
         .into_iter()
         .map(|message| {
             let block = decode_block_from_bytes(&message, content_type.clone())?;
-            let (verified, number) = block_is_verified(&block);
-            if !verified {
-                Err(DecoderError::VerificationFailed {
+            match block_is_verified(&block, &verify_options) {
+                (BlockVerification::Failed, number) => Err(DecoderError::VerificationFailed {
                     block_number: number,
-                })
-            } else {
-                Ok(block)
+                }),
+                _ => Ok(block),
             }
         })
         .collect()
 }
 
+/// Read blocks from a flat file reader, collecting per-message failures instead of aborting.
+///
+/// Returns the blocks which were decoded and verified, together with the index of each message
+/// which could not be, and the error it raised. This recovers the good blocks of a partially
+/// corrupt file. Errors reading the dbin header or framing still fail the whole file.
+///
+/// See [`read_blocks_from_reader`] for details.
+pub fn read_blocks_from_reader_lenient<R: Read>(
+    reader: R,
+    compression: Compression,
+) -> Result<(Vec<AnyBlock>, Vec<(usize, DecoderError)>), DecoderError> {
+    let mut file_contents = decompressed(reader, compression)?;
+
+    let dbin_file = DbinFile::try_from_read(&mut file_contents)?;
+    let content_type: ContentType = dbin_file.content_type().try_into()?;
+
+    let mut blocks = Vec::new();
+    let mut failures = Vec::new();
+    for (index, message) in dbin_file.into_iter().enumerate() {
+        let block = match decode_block_from_bytes(&message, content_type.clone()) {
+            Ok(block) => block,
+            Err(e) => {
+                failures.push((index, e));
+                continue;
+            }
+        };
+
+        match block_is_verified(&block, &VerifyOptions::default()) {
+            (BlockVerification::Failed, number) => failures.push((
+                index,
+                DecoderError::VerificationFailed {
+                    block_number: number,
+                },
+            )),
+            _ => blocks.push(block),
+        }
+    }
+
+    Ok((blocks, failures))
+}
+
+/// Read the blocks of a flat file reader whose numbers fall within `range`.
+///
+/// Every message is decoded, but only blocks within the range are verified and returned, so
+/// the root and hash checks are skipped for unwanted blocks.
+///
+/// # Arguments
+///
+/// * `reader`: A readable source of the file contents, implementing the [`Read`] trait.
+/// * `compression`: The compression type applied to the flat file's data, if any.
+/// * `range`: The block numbers to return, see [`AnyBlock::number`].
+pub fn read_blocks_in_range<R: Read>(
+    reader: R,
+    compression: Compression,
+    range: RangeInclusive<u64>,
+) -> Result<Vec<AnyBlock>, DecoderError> {
+    let mut file_contents = decompressed(reader, compression)?;
+
+    let dbin_file = DbinFile::try_from_read(&mut file_contents)?;
+    let content_type: ContentType = dbin_file.content_type().try_into()?;
+
+    let mut blocks = Vec::new();
+    for message in dbin_file {
+        let block = decode_block_from_bytes(&message, content_type.clone())?;
+        if !range.contains(&block.number()) {
+            continue;
+        }
+
+        if let (BlockVerification::Failed, number) =
+            block_is_verified(&block, &VerifyOptions::default())
+        {
+            return Err(DecoderError::VerificationFailed {
+                block_number: number,
+            });
+        }
+        blocks.push(block);
+    }
+
+    Ok(blocks)
+}
+
+/// Check that a slice of decoded Ethereum blocks forms a chain.
+///
+/// Each block must follow the previous one by number, and its header's parent hash must equal
+/// the hash of the previous block. Returns an error describing the first break in the chain.
+pub fn verify_block_chain(blocks: &[Block]) -> Result<(), DecoderError> {
+    for pair in blocks.windows(2) {
+        let (previous, block) = (&pair[0], &pair[1]);
+
+        let expected = previous.number + 1;
+        if block.number != expected {
+            return Err(DecoderError::BlockNumberGap {
+                expected,
+                found: block.number,
+            });
+        }
+        if block.header()?.parent_hash != previous.hash {
+            return Err(DecoderError::ParentHashMismatch {
+                block_number: block.number,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Aggregate statistics collected while decoding a flat file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecodeStats {
+    /// Number of blocks decoded and returned, including unverifiable blocks.
+    pub total_blocks: u64,
+    /// Number of transactions across all verified blocks.
+    pub total_transactions: u64,
+    /// Gas used across all verified blocks. Only EVM blocks contribute to this total.
+    pub total_gas: u128,
+    /// Lowest block number seen, if any block was decoded.
+    pub min_block_number: Option<u64>,
+    /// Highest block number seen, if any block was decoded.
+    pub max_block_number: Option<u64>,
+    /// Number of blocks which failed verification and were skipped.
+    pub verification_warnings: u64,
+    /// Number of blocks which cannot be verified from their contents, such as Arbitrum
+    /// blocks, and were returned unverified.
+    pub unverifiable_blocks: u64,
+}
+
+impl DecodeStats {
+    fn record_block(&mut self, block: &AnyBlock, block_number: u64) {
+        self.total_blocks += 1;
+        match block {
+            AnyBlock::Evm(eth_block) | AnyBlock::Arb(eth_block) => {
+                self.total_transactions += eth_block.transaction_traces.len() as u64;
+                if let Some(header) = &eth_block.header {
+                    self.total_gas += header.gas_used as u128;
+                }
+            }
+            AnyBlock::Sol(sol_block) => {
+                self.total_transactions += sol_block.transactions.len() as u64;
+            }
+        }
+        self.min_block_number = Some(
+            self.min_block_number
+                .map_or(block_number, |min| min.min(block_number)),
+        );
+        self.max_block_number = Some(
+            self.max_block_number
+                .map_or(block_number, |max| max.max(block_number)),
+        );
+    }
+}
+
+/// Read blocks from a flat file reader, collecting [`DecodeStats`] in the same pass.
+///
+/// Unlike [`read_blocks_from_reader`], blocks which fail verification do not abort
+/// decoding. They are logged, counted in [`DecodeStats::verification_warnings`] and
+/// left out of the returned blocks.
+///
+/// # Arguments
+///
+/// * `reader`: A readable source of the file contents, implementing the [`Read`] trait.
+/// * `compression`: The compression type applied to the flat file's data, if any.
+pub fn read_blocks_with_stats<R: Read>(
+    reader: R,
+    compression: Compression,
+) -> Result<(Vec<AnyBlock>, DecodeStats), DecoderError> {
+    let mut file_contents = decompressed(reader, compression)?;
+
+    let dbin_file = DbinFile::try_from_read(&mut file_contents)?;
+    let content_type: ContentType = dbin_file.content_type().try_into()?;
+
+    let mut stats = DecodeStats::default();
+    let mut blocks = Vec::new();
+
+    for message in dbin_file {
+        let block = decode_block_from_bytes(&message, content_type.clone())?;
+        match block_is_verified(&block, &VerifyOptions::default()) {
+            (BlockVerification::Failed, number) => {
+                info!("Block verification failed, skipping block {}", number);
+                stats.verification_warnings += 1;
+            }
+            (verification, number) => {
+                if verification == BlockVerification::Unverifiable {
+                    stats.unverifiable_blocks += 1;
+                }
+                stats.record_block(&block, number);
+                blocks.push(block);
+            }
+        }
+    }
+
+    Ok((blocks, stats))
+}
+
+/// Extension of dbin flat files.
+const DBIN_EXTENSION: &str = "dbin";
+
+/// Read and verify the blocks of every `.dbin` file in a directory.
+///
+/// Files are read one after another in file name order, and their blocks are concatenated.
+/// Files with a `.dbin.zst` extension are included too; all files are decompressed according to
+/// `compression`. Returns the error of the first file which fails to decode.
+///
+/// # Arguments
+///
+/// * `path`: Directory containing the flat files.
+/// * `compression`: The compression type applied to the flat files' data, if any.
+pub fn read_flat_files<P: AsRef<Path>>(
+    path: P,
+    compression: Compression,
+) -> Result<Vec<AnyBlock>, DecoderError> {
+    let mut blocks = Vec::new();
+    for path in flat_file_paths(path.as_ref())? {
+        blocks.extend(read_flat_file(&path, compression)?);
+    }
+    Ok(blocks)
+}
+
+/// Read and verify the blocks of every `.dbin` file in a directory, using several threads.
+///
+/// Files are split into contiguous batches in file name order, one batch per thread. The result
+/// is the same as [`read_flat_files`]: blocks are returned in file name order, and if any file
+/// fails to decode, the error of the first failing file is returned.
+///
+/// # Arguments
+///
+/// * `path`: Directory containing the flat files.
+/// * `compression`: The compression type applied to the flat files' data, if any.
+/// * `num_threads`: Number of threads to decode with. At least one thread is used.
+pub fn read_flat_files_parallel<P: AsRef<Path>>(
+    path: P,
+    compression: Compression,
+    num_threads: usize,
+) -> Result<Vec<AnyBlock>, DecoderError> {
+    let paths = flat_file_paths(path.as_ref())?;
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let batch_size = paths.len().div_ceil(num_threads.clamp(1, paths.len()));
+
+    let batches: Vec<Result<Vec<Vec<AnyBlock>>, DecoderError>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(batch_size)
+            .map(|batch| {
+                scope.spawn(move || {
+                    batch
+                        .iter()
+                        .map(|path| read_flat_file(path, compression))
+                        .collect::<Result<Vec<_>, DecoderError>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+
+    let mut blocks = Vec::new();
+    for batch in batches {
+        blocks.extend(batch?.into_iter().flatten());
+    }
+    Ok(blocks)
+}
+
+/// Paths of the `.dbin` and `.dbin.zst` files in a directory, sorted by file name.
+fn flat_file_paths(dir: &Path) -> Result<Vec<PathBuf>, DecoderError> {
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let effective_path = if path.extension().is_some_and(|extension| extension == "zst") {
+            path.with_extension("")
+        } else {
+            path.clone()
+        };
+        if effective_path
+            .extension()
+            .is_some_and(|extension| extension == DBIN_EXTENSION)
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn read_flat_file(path: &Path, compression: Compression) -> Result<Vec<AnyBlock>, DecoderError> {
+    trace!("Processing file: {}", path.display());
+    read_blocks_from_reader(BufReader::new(File::open(path)?), compression)
+}
+
+/// Number of blocks in a pre-merge Ethereum epoch.
+const EPOCH_SIZE: u64 = 8192;
+
+/// Split a flat file into one `.dbin` file per 8192-block epoch.
+///
+/// Blocks are decoded to find their block number, but are not verified. Each message is copied
+/// as-is into the file of its epoch, which is written to `output_dir` as a `.dbin` file named
+/// after the first block number of the epoch, e.g. `0000008192.dbin`. Each file keeps the header
+/// of the input, so its version, content type and content version are unchanged. Existing files
+/// with the same name are overwritten.
+///
+/// Only EVM blocks can be split by epoch.
+///
+/// # Arguments
+///
+/// * `reader`: A readable source of the file contents, implementing the [`Read`] trait.
+/// * `compression`: The compression type applied to the flat file's data, if any.
+/// * `output_dir`: Directory in which the per-epoch files are written.
+///
+/// Returns the paths of the written files, ordered by epoch.
+pub fn split_by_epoch<R: Read, P: AsRef<Path>>(
+    reader: R,
+    compression: Compression,
+    output_dir: P,
+) -> Result<Vec<PathBuf>, DecoderError> {
+    let mut file_contents = decompressed(reader, compression)?;
+
+    let dbin_file = DbinFile::try_from_read(&mut file_contents)?;
+    let content_type = dbin_file.content_type().to_string();
+    if ContentType::try_from(content_type.as_str())? != ContentType::Evm {
+        return Err(DecoderError::ContentTypeInvalid(content_type));
+    }
+    let header = dbin_file.header().clone();
+
+    let mut writers: BTreeMap<u64, (PathBuf, BufWriter<File>)> = BTreeMap::new();
+
+    for message in dbin_file {
+        let block_number = match decode_block_from_bytes(&message, ContentType::Evm)? {
+            AnyBlock::Evm(block) => block.number,
+            _ => return Err(DecoderError::ConversionError),
+        };
+        let epoch = block_number / EPOCH_SIZE;
+
+        if !writers.contains_key(&epoch) {
+            let path = output_dir
+                .as_ref()
+                .join(format!("{:010}.dbin", epoch * EPOCH_SIZE));
+            let mut writer = BufWriter::new(File::create(&path)?);
+            header.write_to(&mut writer)?;
+            writers.insert(epoch, (path, writer));
+        }
+
+        if let Some((_, writer)) = writers.get_mut(&epoch) {
+            write_message(writer, &message)?;
+        }
+    }
+
+    writers
+        .into_values()
+        .map(|(path, mut writer)| {
+            writer.flush()?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Outcome of checking a block with [`block_is_verified`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BlockVerification {
+    /// The contents of the block match its header.
+    Verified,
+    /// The contents of the block do not match its header.
+    Failed,
+    /// The block cannot be checked from its contents, so it is neither verified nor failed.
+    Unverifiable,
+}
+
 /// Validate the contents of the Block (e.g., transactions, receipts, block hash)
 /// against the self-contained information in the block (such as Merkle
 /// tree roots). This is a check that the contents of the block are correct,
 /// but does not validate the inclusion of the Block in the chain's
 /// history (as in crates/header-accumulator).
-fn block_is_verified(block: &AnyBlock) -> (bool, u64) {
+fn block_is_verified(block: &AnyBlock, verify_options: &VerifyOptions) -> (BlockVerification, u64) {
     match block {
         // Validate the transactions and receipts in the Block by
         // reconstructing the transactions and receipts trees and
@@ -181,36 +708,52 @@ fn block_is_verified(block: &AnyBlock) -> (bool, u64) {
         AnyBlock::Evm(eth_block) => {
             let block_number = eth_block.number;
             if block_number != 0 {
-                if !eth_block.receipt_root_is_verified() {
+                if verify_options.receipt_root && !eth_block.receipt_root_is_verified() {
                     error!(
                         "Receipt root verification failed for block {}",
                         block_number
                     );
-                    return (false, block_number);
+                    return (BlockVerification::Failed, block_number);
                 }
-                if !eth_block.transaction_root_is_verified() {
+                if verify_options.transaction_root && !eth_block.transaction_root_is_verified() {
                     error!(
                         "Transaction root verification failed for block {}",
                         block_number
                     );
-                    return (false, block_number);
+                    return (BlockVerification::Failed, block_number);
                 }
-                if !eth_block.block_hash_is_verified() {
+                if verify_options.block_hash && !eth_block.block_hash_is_verified() {
                     error!("Block hash verification failed for block {}", block_number);
-                    return (false, block_number);
+                    return (BlockVerification::Failed, block_number);
+                }
+                if verify_options.gas_used
+                    && !matches!(eth_block.gas_used_is_consistent(), Ok(true))
+                {
+                    error!(
+                        "Gas used consistency check failed for block {}",
+                        block_number
+                    );
+                    return (BlockVerification::Failed, block_number);
                 }
             }
-            (true, block_number)
+            (BlockVerification::Verified, block_number)
         }
-        // Logic is not yet implemented for verifying Solana Blocks.
-        // The blockhash can be used to verify transactions, but
-        // the information needed to reconstruct the blockhash is not
-        // self-contained in the Block. Additional framework is needed
-        // to implement this function for Solana.
+        // The blockhash can be used to verify transactions, but the
+        // information needed to reconstruct the blockhash is not
+        // self-contained in the Block, so only the internal consistency
+        // of Solana Blocks is checked.
         AnyBlock::Sol(sol_block) => {
-            let block_number = sol_block.block_height.unwrap().block_height;
-            (true, block_number)
+            let verification = if sol_block.sol_block_is_verified() {
+                BlockVerification::Verified
+            } else {
+                BlockVerification::Failed
+            };
+            (verification, block.number())
         }
+        // Arbitrum blocks contain Arbitrum-specific transaction types, which
+        // are not supported when reconstructing the transactions and receipts
+        // trees, so they cannot be verified yet.
+        AnyBlock::Arb(arb_block) => (BlockVerification::Unverifiable, arb_block.number),
     }
 }
 
@@ -230,13 +773,13 @@ impl Reader {
     pub(crate) fn into_reader(self) -> Result<Box<dyn Read>, DecoderError> {
         match self {
             Reader::StdIn(compression) => match compression {
-                Compression::Zstd => Ok(Box::new(zstd::stream::Decoder::new(std::io::stdin())?)),
                 Compression::None => Ok(Box::new(BufReader::with_capacity(
                     // Set buffer size to 128 MB (64 * 2 MB) for reading large data efficiently.
                     // `(64 * 2) << 20` converts 128 MB to bytes (128 * 1,048,576 = 134,217,728 bytes).
                     (64 * 2) << 20,
                     std::io::stdin().lock(),
                 ))),
+                compression => decompressed(std::io::stdin(), compression),
             },
             Reader::Buf(reader) => Ok(Box::new(reader)),
         }
@@ -254,95 +797,161 @@ impl TryFrom<Reader> for Box<dyn Read> {
 /// Set the end block for the range of blocks to read, decode, and verify.
 ///
 /// Enum to handle the end block of the stream.
-/// It can be the merge block, i.e. the last pre-merge block, or a specific block number.
+/// When the reader reaches the end of its input before the end block has been read, the stream
+/// waits for more blocks. With [`EndBlock::Unbounded`], the default, the stream never waits for
+/// a block number and ends only when the reader ends or fails.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum EndBlock {
     /// The last pre-merge block.
     MergeBlock,
     /// A specific block number.
     Block(u64),
+    /// No end block, stream until the reader ends.
+    #[default]
+    Unbounded,
 }
 
 impl EndBlock {
-    fn block_number(&self) -> u64 {
+    fn block_number(&self) -> Option<u64> {
         const LAST_PREMERGE_BLOCK: u64 = 15537393;
         match self {
-            EndBlock::MergeBlock => LAST_PREMERGE_BLOCK,
-            EndBlock::Block(block_number) => *block_number,
+            EndBlock::MergeBlock => Some(LAST_PREMERGE_BLOCK),
+            EndBlock::Block(block_number) => Some(*block_number),
+            EndBlock::Unbounded => None,
         }
     }
 }
 
 impl From<Option<u64>> for EndBlock {
     fn from(value: Option<u64>) -> Self {
-        value.map_or(EndBlock::MergeBlock, EndBlock::Block)
+        value.map_or(EndBlock::Unbounded, EndBlock::Block)
     }
 }
 
 /// Get an iterator of decoded, verified blocks from a reader.
 ///
-/// Skips invalid blocks and returns an iterator of verified blocks.
+/// Blocks are read and decoded lazily, one per call to `next()`, so the whole file is never held
+/// in memory. Each item is the decoded block, or the error that occurred while reading, decoding
+/// or verifying it. Blocks which fail verification yield [`DecoderError::VerificationFailed`]
+/// and the iterator moves on to the next block. Any other read error ends the iteration.
+///
+/// The reader may hold several concatenated `.dbin` files. Each embedded header switches the
+/// content type used to decode the blocks which follow it, so a stream can mix chains, such
+/// as Ethereum blocks followed by Solana blocks.
 ///
 /// # Arguments
 ///
 /// * `reader`: A [`Reader`] enum that specifies the source of the block data. The reader can be a
 ///   [`BufReader`] or a `StdIn` reader with or without compression.
-/// * `end_block`: Specifies the block number at which to stop streaming. By default, this is set to
-///   block 15537393, the last block before the Ethereum merge.
+/// * `end_block`: Specifies the block number at which to stop streaming, see [`EndBlock`].
 pub fn stream_blocks(
     reader: Reader,
     end_block: EndBlock,
-) -> Result<impl Iterator<Item = AnyBlock>, DecoderError> {
-    let mut current_block_number = 0;
-
+) -> Result<impl Iterator<Item = Result<AnyBlock, DecoderError>>, DecoderError> {
     let mut reader = reader.into_reader()?;
-    let end_block = end_block.block_number();
-
-    let mut blocks = Vec::new();
 
     let header = DbinHeader::try_from_read(&mut reader)?;
     let content_type: ContentType = header.content_type().try_into()?;
 
-    loop {
-        match read_block_from_reader(&mut reader) {
-            Ok(message) => {
-                match decode_block_from_bytes(&message, content_type.clone()) {
-                    Ok(block) => {
-                        let (verified, number) = block_is_verified(&block);
-                        current_block_number = number;
-                        if verified {
-                            blocks.push(block);
-                        } else {
-                            info!("Block verification failed, skipping block {}", number);
+    Ok(BlockStream {
+        reader,
+        content_type,
+        current_block_number: 0,
+        end_block: end_block.block_number(),
+        finished: false,
+    })
+}
+
+/// Lazy iterator returned by [`stream_blocks`].
+struct BlockStream {
+    reader: Box<dyn Read>,
+    content_type: ContentType,
+    current_block_number: u64,
+    end_block: Option<u64>,
+    finished: bool,
+}
+
+impl Iterator for BlockStream {
+    type Item = Result<AnyBlock, DecoderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            match read_block_and_header_from_reader(&mut self.reader) {
+                Ok((header, message)) => {
+                    if let Some(header) = header {
+                        self.content_type = match header.content_type().try_into() {
+                            Ok(content_type) => content_type,
+                            Err(e) => {
+                                self.finished = true;
+                                return Some(Err(e));
+                            }
+                        };
+                    }
+                    let block = match decode_block_from_bytes(&message, self.content_type.clone()) {
+                        Ok(block) => block,
+                        Err(e) => return Some(Err(e)),
+                    };
+                    let (verification, number) =
+                        block_is_verified(&block, &VerifyOptions::default());
+                    self.current_block_number = number;
+                    if verification == BlockVerification::Failed {
+                        return Some(Err(DecoderError::VerificationFailed {
+                            block_number: number,
+                        }));
+                    }
+                    return Some(Ok(block));
+                }
+                Err(DecoderError::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    match self.end_block {
+                        Some(end_block) if self.current_block_number < end_block => {
+                            info!("Reached end of file, waiting for more blocks");
                         }
+                        _ => self.finished = true,
                     }
-                    Err(e) => return Err(e),
-                };
-            }
-            Err(DecoderError::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
-                if current_block_number < end_block {
-                    info!("Reached end of file, waiting for more blocks");
-                    continue;
                 }
-                break;
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Decodes an Ethereum block from a byte slice, with or without [`BstreamBlock`] framing.
+///
+/// The bytes are first decoded as a [`BstreamBlock`] wrapping the block, as stored in flat
+/// files. If that fails, they are decoded as a bare prost-encoded [`Block`], such as a block
+/// received from a gRPC response.
+pub fn decode_eth_block(bytes: &[u8]) -> Result<Block, DecoderError> {
+    if let Ok(block_stream) = BstreamBlock::decode(bytes) {
+        let block_stream_payload = bstream_payload(block_stream);
+        if !block_stream_payload.is_empty() {
+            if let Ok(block) = Block::decode(block_stream_payload.as_slice()) {
+                return Ok(block);
             }
-            Err(e) => return Err(e),
         }
     }
 
-    Ok(blocks.into_iter())
+    Ok(Block::decode(bytes)?)
 }
 
-/// Decodes a block from a byte slice.
+/// Returns the payload of a [`BstreamBlock`], falling back to the deprecated payload buffer.
 #[allow(deprecated)]
+fn bstream_payload(block_stream: BstreamBlock) -> Vec<u8> {
+    block_stream
+        .payload
+        .map(|p| p.value)
+        .unwrap_or(block_stream.payload_buffer)
+}
+
+/// Decodes a block from a byte slice.
 fn decode_block_from_bytes(
     bytes: &[u8],
     content_type: ContentType,
 ) -> Result<AnyBlock, DecoderError> {
-    let block_stream = BstreamBlock::decode(bytes)?;
-    let block_stream_payload = block_stream
-        .payload
-        .map(|p| p.value)
-        .unwrap_or(block_stream.payload_buffer);
+    let block_stream_payload = bstream_payload(BstreamBlock::decode(bytes)?);
 
     match content_type {
         ContentType::Evm => {
@@ -353,6 +962,10 @@ fn decode_block_from_bytes(
             let block = SolBlock::decode(block_stream_payload.as_slice())?;
             Ok(AnyBlock::Sol(block))
         }
+        ContentType::Arb => {
+            let block = Block::decode(block_stream_payload.as_slice())?;
+            Ok(AnyBlock::Arb(block))
+        }
     }
 }
 
@@ -381,10 +994,10 @@ pub fn parquet_to_headers(file: File) -> Result<Vec<BlockHeader>, parquet::error
             transactions_root: row.get_bytes(7)?.data().to_vec(),
             receipt_root: row.get_bytes(8)?.data().to_vec(),
             logs_bloom: row.get_bytes(9)?.data().to_vec(),
-            difficulty: Some(BigInt {
-                bytes: row.get_bytes(10)?.data().to_vec(),
-            }),
-            // total_difficulty is not present in parquet headers
+            difficulty: Some(decimal_to_big_int(row.get_decimal(10)?)),
+            // total_difficulty is not present in parquet headers, so it is intentionally left
+            // empty. It is not part of the block hash, but callers relying on it must fill it in
+            // from another source.
             total_difficulty: Some(BigInt { bytes: vec![] }),
             gas_limit: row.get_ulong(11).unwrap(),
             gas_used: row.get_ulong(12).unwrap(),
@@ -396,12 +1009,11 @@ pub fn parquet_to_headers(file: File) -> Result<Vec<BlockHeader>, parquet::error
                 })
                 .ok(),
             extra_data: row.get_bytes(13)?.data().to_vec(),
-            mix_hash: row.get_bytes(15)?.data().to_vec(),
-            nonce: row.get_ulong(16).unwrap(),
+            mix_hash: row.get_bytes(14)?.data().to_vec(),
+            nonce: row.get_ulong(15).unwrap(),
             hash: row.get_bytes(2)?.data().to_vec(),
-            base_fee_per_gas: Some(BigInt {
-                bytes: row.get_bytes(16)?.data().to_vec(),
-            }),
+            // base_fee_per_gas is null before the London fork
+            base_fee_per_gas: optional_big_int(column(&row, 16)?)?,
             // withdrawals_root not present in parquet headers
             withdrawals_root: vec![],
             // tx_dependency is not present in parquet files
@@ -417,16 +1029,78 @@ pub fn parquet_to_headers(file: File) -> Result<Vec<BlockHeader>, parquet::error
     Ok(bheaders)
 }
 
+/// Returns the field of `row` at `index`.
+fn column(row: &Row, index: usize) -> Result<&Field, ParquetError> {
+    row.get_column_iter()
+        .nth(index)
+        .map(|(_, field)| field)
+        .ok_or_else(|| ParquetError::General(format!("missing column {index}")))
+}
+
+/// Converts a nullable decimal field into a [`BigInt`], or `None` if it is null.
+fn optional_big_int(field: &Field) -> Result<Option<BigInt>, ParquetError> {
+    match field {
+        Field::Null => Ok(None),
+        Field::Decimal(decimal) => Ok(Some(decimal_to_big_int(decimal))),
+        other => Err(ParquetError::General(format!(
+            "expected a decimal, found {other}"
+        ))),
+    }
+}
+
+/// Converts a parquet decimal into a [`BigInt`], whose bytes are big-endian without leading
+/// zeros.
+fn decimal_to_big_int(decimal: &Decimal) -> BigInt {
+    let bytes = decimal.data();
+    let first_non_zero = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+    BigInt {
+        bytes: bytes[first_non_zero..].to_vec(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::File;
 
     use super::*;
+    use crate::dbin::write_header_v1;
 
     #[test]
     fn test_read_parquet() {
         let file = File::open("tests/000000000.parquet").unwrap();
-        let _ = parquet_to_headers(file);
+        let headers = parquet_to_headers(file).unwrap();
+
+        let genesis = headers.iter().find(|header| header.number == 0).unwrap();
+        assert_eq!(genesis.nonce, 0x42);
+        assert_eq!(genesis.mix_hash, vec![0; 32]);
+        // 0x400000000
+        assert_eq!(
+            genesis.difficulty.as_ref().unwrap().bytes,
+            vec![4, 0, 0, 0, 0]
+        );
+        // Pre-London blocks have no base fee
+        assert_eq!(genesis.base_fee_per_gas, None);
+    }
+
+    #[test]
+    fn test_optional_big_int() {
+        // 1 gwei, the base fee of the first post-London block
+        let base_fee = Decimal::from_bytes(
+            parquet::data_type::ByteArray::from(vec![0, 0, 0, 0x3b, 0x9a, 0xca, 0x00]),
+            38,
+            0,
+        );
+        assert_eq!(
+            optional_big_int(&Field::Decimal(base_fee)).unwrap(),
+            Some(BigInt {
+                bytes: vec![0x3b, 0x9a, 0xca, 0x00]
+            })
+        );
+        assert_eq!(optional_big_int(&Field::Null).unwrap(), None);
+        assert!(optional_big_int(&Field::Int(1)).is_err());
     }
 
     #[test]
@@ -434,7 +1108,22 @@ mod tests {
         let file = File::open("tests/0000000000.dbin").unwrap();
         let mut reader = BufReader::new(file);
 
-        let _block = read_blocks_from_reader(&mut reader, false.into()).unwrap();
+        let blocks = read_blocks_from_reader(&mut reader, false.into()).unwrap();
+        assert_eq!(blocks.len(), 100);
+        assert!(blocks.iter().all(AnyBlock::is_eth_block));
+    }
+
+    #[test]
+    fn test_read_eth_blocks_from_reader() {
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let blocks = read_eth_blocks_from_reader(BufReader::new(file), Compression::None).unwrap();
+        assert_eq!(blocks.first().unwrap().number, 0);
+
+        let file = File::open("tests/0325942300.dbin.zst").unwrap();
+        assert!(matches!(
+            read_eth_blocks_from_reader(BufReader::new(file), Compression::Zstd),
+            Err(DecoderError::ConversionError)
+        ));
     }
 
     #[test]
@@ -442,7 +1131,382 @@ mod tests {
         let file = File::open("tests/0325942300.dbin.zst").unwrap();
         let mut reader = BufReader::new(file);
 
-        let _block = read_blocks_from_reader(&mut reader, true.into()).unwrap();
+        let blocks = read_blocks_from_reader(&mut reader, true.into()).unwrap();
+        assert_eq!(blocks.len(), 100);
+        assert!(blocks.iter().all(AnyBlock::is_sol_block));
+        assert!(blocks
+            .iter()
+            .filter_map(AnyBlock::as_sol_block)
+            .all(SolBlock::sol_block_is_verified));
+    }
+
+    #[test]
+    fn test_sol_block_transaction_stats() {
+        let file = File::open("tests/0325942300.dbin.zst").unwrap();
+        let mut reader = BufReader::new(file);
+
+        let blocks = read_blocks_from_reader(&mut reader, true.into()).unwrap();
+        let block = blocks[0].as_sol_block().unwrap();
+        assert_eq!(block.slot, 325942300);
+        assert_eq!(block.transaction_count(), 2759);
+        assert_eq!(block.successful_transaction_count(), 2576);
+        assert_eq!(block.total_compute_units_consumed(), 43158034);
+    }
+
+    #[test]
+    fn test_read_gzip_blocks() {
+        let file = File::open("tests/0000000001.dbin.gz").unwrap();
+        let blocks = read_eth_blocks_from_reader(BufReader::new(file), "gz".into()).unwrap();
+        assert_eq!(blocks.len(), 1);
+
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let uncompressed =
+            read_eth_blocks_from_reader(BufReader::new(file), Compression::None).unwrap();
+        assert_eq!(blocks[0], uncompressed[1]);
+
+        let info = identify("tests/0000000001.dbin.gz").unwrap();
+        assert_eq!(info.first_block_number, Some(1));
+        assert_eq!(info.message_count, 1);
+    }
+
+    #[test]
+    fn test_read_zstd_blocks_streaming() {
+        let compressed = std::fs::read("tests/0325942300.dbin.zst").unwrap();
+        let decompressed = zstd::decode_all(compressed.as_slice()).unwrap();
+
+        let streamed: Vec<SolBlock> =
+            read_blocks_from_reader(compressed.as_slice(), Compression::Zstd)
+                .unwrap()
+                .into_iter()
+                .map(|block| block.try_into_sol_block().unwrap())
+                .collect();
+        let eager: Vec<SolBlock> =
+            read_blocks_from_reader(decompressed.as_slice(), Compression::None)
+                .unwrap()
+                .into_iter()
+                .map(|block| block.try_into_sol_block().unwrap())
+                .collect();
+
+        assert!(!streamed.is_empty());
+        assert_eq!(streamed, eager);
+    }
+
+    #[test]
+    fn test_read_blocks_with_stats() {
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let reader = BufReader::new(file);
+        let (blocks, stats) = read_blocks_with_stats(reader, Compression::None).unwrap();
+
+        assert_eq!(stats.total_blocks, blocks.len() as u64);
+        assert_eq!(stats.total_blocks, 100);
+        assert_eq!(stats.min_block_number, Some(0));
+        assert_eq!(stats.max_block_number, Some(99));
+        assert_eq!(stats.verification_warnings, 0);
+    }
+
+    #[test]
+    fn test_read_flat_files_parallel() {
+        let dir = std::env::temp_dir().join("decoder_test_read_flat_files_parallel");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy("tests/0000000000.dbin", dir.join("0000000000.dbin")).unwrap();
+        std::fs::copy("tests/0000000000.dbin", dir.join("0000000100.dbin")).unwrap();
+
+        let into_eth_blocks = |blocks: Vec<AnyBlock>| -> Vec<Block> {
+            blocks
+                .into_iter()
+                .map(|block| block.try_into_eth_block().unwrap())
+                .collect()
+        };
+        let sequential = into_eth_blocks(read_flat_files(&dir, Compression::None).unwrap());
+        let parallel =
+            into_eth_blocks(read_flat_files_parallel(&dir, Compression::None, 2).unwrap());
+
+        assert_eq!(sequential.len(), 200);
+        assert_eq!(parallel, sequential);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_identify() {
+        let info = identify("tests/0000000000.dbin").unwrap();
+        assert_eq!(info.chain, Some(ContentType::Evm));
+        assert_eq!(info.first_block_number, Some(0));
+        assert_eq!(info.message_count, 100);
+
+        let info = identify("tests/0325942300.dbin.zst").unwrap();
+        assert_eq!(info.chain, Some(ContentType::Sol));
+        assert_eq!(info.version, Version::V1);
+    }
+
+    #[test]
+    fn test_split_by_epoch() {
+        let output_dir = std::env::temp_dir().join("decoder_test_split_by_epoch");
+        std::fs::create_dir_all(&output_dir).unwrap();
+
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let paths = split_by_epoch(BufReader::new(file), Compression::None, &output_dir).unwrap();
+
+        assert_eq!(paths, vec![output_dir.join("0000000000.dbin")]);
+
+        let reader = BufReader::new(File::open(&paths[0]).unwrap());
+        let blocks = read_blocks_from_reader(reader, Compression::None).unwrap();
+        assert_eq!(blocks.len(), 100);
+
+        std::fs::remove_dir_all(&output_dir).unwrap();
+    }
+
+    #[test]
+    fn test_split_by_epoch_keeps_header() {
+        let data = std::fs::read("tests/0000000000.dbin").unwrap();
+        let dbin_file = DbinFile::try_from_read(data.as_slice()).unwrap();
+        let content_type = "type.googleapis.com/sf.ethereum.type.v2.Block";
+        let mut v1 = vec![];
+        write_header_v1(&mut v1, content_type).unwrap();
+        for message in dbin_file {
+            write_message(&mut v1, &message).unwrap();
+        }
+
+        for (input, version, expected_type, content_version) in [
+            (data.as_slice(), Version::V0, "ETH", Some("01")),
+            (v1.as_slice(), Version::V1, content_type, None),
+        ] {
+            let output_dir = std::env::temp_dir().join(format!(
+                "decoder_test_split_by_epoch_keeps_header_{version:?}"
+            ));
+            std::fs::create_dir_all(&output_dir).unwrap();
+
+            let paths = split_by_epoch(input, Compression::None, &output_dir).unwrap();
+            let reader = BufReader::new(File::open(&paths[0]).unwrap());
+            let split = DbinFile::try_from_read(reader).unwrap();
+
+            assert_eq!(split.version(), version);
+            assert_eq!(split.content_type(), expected_type);
+            assert_eq!(split.content_version(), content_version);
+            assert_eq!(split.into_iter().count(), 100);
+
+            std::fs::remove_dir_all(&output_dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_decode_eth_block() {
+        let mut reader = BufReader::new(File::open("tests/0000000000.dbin").unwrap());
+        let message = DbinFile::try_from_read(&mut reader)
+            .unwrap()
+            .into_iter()
+            .nth(1)
+            .unwrap();
+
+        let block = decode_eth_block(&message).unwrap();
+        assert_eq!(block.number, 1);
+
+        let bare = block.encode_to_vec();
+        assert_eq!(decode_eth_block(&bare).unwrap(), block);
+
+        assert!(decode_eth_block(b"not a block").is_err());
+    }
+
+    #[test]
+    fn test_verify_block_chain() {
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let mut blocks =
+            read_eth_blocks_from_reader(BufReader::new(file), Compression::None).unwrap();
+        assert!(verify_block_chain(&blocks).is_ok());
+        assert!(verify_block_chain(&blocks[..1]).is_ok());
+        assert!(verify_block_chain(&[]).is_ok());
+
+        let mut gap = blocks.clone();
+        gap.remove(50);
+        assert!(matches!(
+            verify_block_chain(&gap),
+            Err(DecoderError::BlockNumberGap {
+                expected: 50,
+                found: 51
+            })
+        ));
+
+        blocks[60].header.as_mut().unwrap().parent_hash[0] ^= 0xff;
+        assert!(matches!(
+            verify_block_chain(&blocks),
+            Err(DecoderError::ParentHashMismatch { block_number: 60 })
+        ));
+    }
+
+    #[test]
+    fn test_read_blocks_from_reader_lenient() {
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let dbin_file = DbinFile::try_from_read(&mut BufReader::new(file)).unwrap();
+        let messages: Vec<Vec<u8>> = dbin_file.into_iter().skip(1).take(2).collect();
+
+        let mut data = b"dbin\x00ETH01".to_vec();
+        write_message(&mut data, &messages[0]).unwrap();
+        write_message(&mut data, b"not a block").unwrap();
+        write_message(&mut data, &messages[1]).unwrap();
+
+        let (blocks, failures) =
+            read_blocks_from_reader_lenient(data.as_slice(), Compression::None).unwrap();
+        assert_eq!(
+            blocks.iter().map(AnyBlock::number).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(failures[0], (1, DecoderError::ProtobufDecode(_))));
+    }
+
+    #[test]
+    fn test_read_blocks_in_range() {
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let blocks = read_blocks_in_range(BufReader::new(file), Compression::None, 2..=3).unwrap();
+        let numbers: Vec<u64> = blocks.iter().map(AnyBlock::number).collect();
+        assert_eq!(numbers, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_read_blocks_with_gas_used_check() {
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let options = VerifyOptions {
+            gas_used: true,
+            ..Default::default()
+        };
+        let blocks =
+            read_blocks_from_reader_with_options(BufReader::new(file), Compression::None, options)
+                .unwrap();
+        assert_eq!(blocks.len(), 100);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_read_blocks_without_verification() {
+        let mut reader = BufReader::new(File::open("tests/0000000000.dbin").unwrap());
+        let dbin_file = DbinFile::try_from_read(&mut reader).unwrap();
+        let content_type = dbin_file.content_type().to_string();
+
+        let mut buffer = Vec::new();
+        write_header_v1(&mut buffer, &content_type).unwrap();
+        for (index, message) in dbin_file.into_iter().enumerate() {
+            let message = if index == 1 {
+                let mut bstream_block = BstreamBlock::decode(message.as_slice()).unwrap();
+                let mut block = Block::decode(bstream_block.payload_buffer.as_slice()).unwrap();
+                block.header.as_mut().unwrap().receipt_root = vec![0xff; 32];
+                bstream_block.payload_buffer = block.encode_to_vec();
+                bstream_block.encode_to_vec()
+            } else {
+                message
+            };
+            write_message(&mut buffer, &message).unwrap();
+        }
+
+        assert!(matches!(
+            read_blocks_from_reader(Cursor::new(&buffer), Compression::None),
+            Err(DecoderError::VerificationFailed { block_number: 1 })
+        ));
+
+        // The block hash covers the receipt root, so it has to be skipped as well.
+        let options = VerifyOptions {
+            receipt_root: false,
+            block_hash: false,
+            ..Default::default()
+        };
+        let blocks =
+            read_blocks_from_reader_with_options(Cursor::new(&buffer), Compression::None, options)
+                .unwrap();
+        assert_eq!(blocks.len(), 100);
+    }
+
+    #[test]
+    fn test_read_arb_blocks_from_reader() {
+        const ARB_CONTENT_TYPE: &str = "type.googleapis.com/sf.arbitrum.type.v1.Block";
+        assert_eq!(
+            ContentType::try_from(ARB_CONTENT_TYPE).unwrap(),
+            ContentType::Arb
+        );
+
+        // Arbitrum Nitro blocks share the EVM Block definition, so reuse the ETH fixture.
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let dbin_file = DbinFile::try_from_read(BufReader::new(file)).unwrap();
+        let mut buffer = Vec::new();
+        write_header_v1(&mut buffer, ARB_CONTENT_TYPE).unwrap();
+        for message in dbin_file {
+            write_message(&mut buffer, &message).unwrap();
+        }
+
+        let blocks = read_blocks_from_reader(buffer.as_slice(), Compression::None).unwrap();
+        assert_eq!(blocks.len(), 100);
+        assert!(blocks.iter().all(AnyBlock::is_arb_block));
+        assert!(blocks.iter().all(AnyBlock::is_eth_block));
+        assert_eq!(blocks[1].as_eth_block().unwrap().number, 1);
+
+        // Arbitrum blocks are returned, but never reported as verified.
+        assert_eq!(
+            block_is_verified(&blocks[1], &VerifyOptions::default()),
+            (BlockVerification::Unverifiable, 1)
+        );
+        let (_, stats) = read_blocks_with_stats(buffer.as_slice(), Compression::None).unwrap();
+        assert_eq!(stats.total_blocks, 100);
+        assert_eq!(stats.unverifiable_blocks, 100);
+        assert_eq!(stats.verification_warnings, 0);
+    }
+
+    #[test]
+    fn test_stream_blocks() {
+        let bytes = std::fs::read("tests/0000000000.dbin").unwrap();
+        let reader = Reader::Buf(BufReader::new(Cursor::new(bytes)));
+        let mut blocks = stream_blocks(reader, EndBlock::Block(99)).unwrap();
+
+        let first = blocks.next().unwrap().unwrap();
+        assert_eq!(first.as_eth_block().unwrap().number, 0);
+        assert_eq!(blocks.count(), 99);
+    }
+
+    #[test]
+    fn test_stream_blocks_unbounded() {
+        let bytes = std::fs::read("tests/0000000000.dbin").unwrap();
+        let reader = Reader::Buf(BufReader::new(Cursor::new(bytes)));
+        let blocks: Vec<AnyBlock> = stream_blocks(reader, EndBlock::default())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(blocks.len(), 100);
+        assert_eq!(blocks.last().unwrap().number(), 99);
+        assert_eq!(EndBlock::from(None), EndBlock::Unbounded);
+    }
+
+    #[test]
+    fn test_stream_blocks_truncated() {
+        let bytes = std::fs::read("tests/0000000000.dbin").unwrap();
+        let reader = Reader::Buf(BufReader::new(Cursor::new(
+            bytes[..bytes.len() - 2].to_vec(),
+        )));
+        let results: Vec<_> = stream_blocks(reader, EndBlock::Unbounded)
+            .unwrap()
+            .collect();
+
+        assert_eq!(results.len(), 100);
+        assert!(results[..99].iter().all(Result::is_ok));
+        assert!(matches!(
+            results[99],
+            Err(DecoderError::TruncatedMessage { expected, got }) if got == expected - 2
+        ));
+    }
+
+    #[test]
+    fn test_stream_blocks_mixed_content_types() {
+        let mut bytes = std::fs::read("tests/0000000000.dbin").unwrap();
+        let compressed = std::fs::read("tests/0325942300.dbin.zst").unwrap();
+        bytes.extend(zstd::decode_all(compressed.as_slice()).unwrap());
+
+        let reader = Reader::Buf(BufReader::new(Cursor::new(bytes)));
+        let blocks: Vec<AnyBlock> = stream_blocks(reader, EndBlock::Unbounded)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(blocks.len(), 200);
+        assert!(blocks[..100].iter().all(AnyBlock::is_eth_block));
+        assert!(blocks[100..].iter().all(AnyBlock::is_sol_block));
+        assert_eq!(blocks[100].as_sol_block().unwrap().slot, 325942300);
     }
 
     #[test]
@@ -10,14 +10,45 @@ pub enum DecoderError {
     #[error("Bin code error: {0}")]
     Bincode(#[from] bincode::error::EncodeError),
 
+    /// [bincode] library decode error.
+    #[error("Bin code decode error: {0}")]
+    BincodeDecode(#[from] bincode::error::DecodeError),
+
+    /// Block does not follow the previous block by number.
+    #[error("Block number gap: expected block {expected}, found block {found}")]
+    BlockNumberGap {
+        /// Number following the previous block.
+        expected: u64,
+        /// Number of the block found instead.
+        found: u64,
+    },
+
     /// Flat file bytes invalid.
     #[error("Invalid flat file bytes")]
     BytesInvalid,
 
+    /// Flat files with different content types.
+    #[error("Flat files with different content types: expected {expected}, found {found}")]
+    ContentTypeConflict {
+        /// Content type of the first flat file.
+        expected: String,
+        /// Conflicting content type.
+        found: String,
+    },
+
     /// Flat file content type invalid.
     #[error("Invalid flat file content type: {0}")]
     ContentTypeInvalid(String),
 
+    /// Flat files with different content versions.
+    #[error("Flat files with different content versions: expected {expected}, found {found}")]
+    ContentVersionConflict {
+        /// Content version of the first flat file.
+        expected: String,
+        /// Conflicting content version.
+        found: String,
+    },
+
     /// Error converting from AnyBlock into chain-specific Block.
     #[error("The block contents of this file are not supported")]
     ConversionError,
@@ -53,6 +84,13 @@ pub enum DecoderError {
         block_number: u64,
     },
 
+    /// Parent hash of a block does not match the hash of the previous block.
+    #[error("Parent hash of block {block_number} does not match the previous block hash")]
+    ParentHashMismatch {
+        /// Block number.
+        block_number: u64,
+    },
+
     /// [prost] library decode error.
     #[error("Protobuf decode error: {0}")]
     ProtobufDecode(#[from] prost::DecodeError),
@@ -69,6 +107,15 @@ pub enum DecoderError {
     #[error("Invalid Transaction Root")]
     TransactionRootInvalid,
 
+    /// Flat file ended in the middle of a message.
+    #[error("Truncated message: expected {expected} bytes, got {got}")]
+    TruncatedMessage {
+        /// Number of bytes declared for the message.
+        expected: usize,
+        /// Number of bytes read before the end of the file.
+        got: usize,
+    },
+
     /// [std::array::TryFromSliceError].
     #[error("TryFromSliceError: {0}")]
     TryFromSlice(#[from] std::array::TryFromSliceError),
@@ -77,6 +124,10 @@ pub enum DecoderError {
     #[error("{0}")]
     Utf8(#[from] std::string::FromUtf8Error),
 
+    /// Unsupported serialization version of a header record.
+    #[error("Unsupported header record version {0}")]
+    UnsupportedRecordVersion(u8),
+
     /// Block verification failed for given block.
     #[error("Block verification failed {block_number}")]
     VerificationFailed {
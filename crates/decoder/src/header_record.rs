@@ -0,0 +1,141 @@
+// SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use firehose_protos::{EthBlock as Block, SolBlock};
+use serde::{Deserialize, Serialize};
+
+use crate::{decoder::AnyBlock, error::DecoderError};
+
+/// Version of the [`HeaderRecordWithNumber`] serialization, written as the first byte of
+/// [`HeaderRecordWithNumber::to_versioned_bytes`].
+pub const HEADER_RECORD_VERSION: u8 = 1;
+
+/// A struct to hold the block hash, block number, and total difficulty of a block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderRecordWithNumber {
+    /// Hash of the block.
+    pub block_hash: Vec<u8>,
+    /// Number of the block, the block height for Solana blocks.
+    pub block_number: u64,
+    /// Big-endian total difficulty of the block, empty for Solana blocks.
+    pub total_difficulty: Vec<u8>,
+}
+
+impl HeaderRecordWithNumber {
+    /// Serializes the record with [bincode], prefixed by the [`HEADER_RECORD_VERSION`] byte.
+    pub fn to_versioned_bytes(&self) -> Result<Vec<u8>, DecoderError> {
+        let mut bytes = vec![HEADER_RECORD_VERSION];
+        bytes.extend(bincode::serde::encode_to_vec(
+            self,
+            bincode::config::standard(),
+        )?);
+        Ok(bytes)
+    }
+
+    /// Deserializes a record written by [`HeaderRecordWithNumber::to_versioned_bytes`].
+    ///
+    /// Returns [`DecoderError::UnsupportedRecordVersion`] if the bytes were written with a
+    /// different serialization version.
+    pub fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, DecoderError> {
+        let (version, record) = bytes.split_first().ok_or(DecoderError::BytesInvalid)?;
+        if *version != HEADER_RECORD_VERSION {
+            return Err(DecoderError::UnsupportedRecordVersion(*version));
+        }
+
+        let (record, _) = bincode::serde::decode_from_slice(record, bincode::config::standard())?;
+        Ok(record)
+    }
+}
+
+/// Try from an Ethereum Block
+impl TryFrom<&Block> for HeaderRecordWithNumber {
+    type Error = DecoderError;
+
+    fn try_from(block: &Block) -> Result<Self, Self::Error> {
+        Ok(HeaderRecordWithNumber {
+            block_hash: block.hash.clone(),
+            block_number: block.number,
+            total_difficulty: block
+                .header()?
+                .total_difficulty
+                .as_ref()
+                .ok_or(Self::Error::TotalDifficultyInvalid)?
+                .bytes
+                .clone(),
+        })
+    }
+}
+
+/// Try from a Solana Block
+impl TryFrom<&SolBlock> for HeaderRecordWithNumber {
+    type Error = DecoderError;
+
+    fn try_from(block: &SolBlock) -> Result<Self, Self::Error> {
+        Ok(HeaderRecordWithNumber {
+            block_hash: block.blockhash.clone().into(),
+            block_number: block
+                .block_height
+                .map_or(block.slot, |height| height.block_height),
+            // There is no field analogous to `total_difficulty` in Solana Blocks
+            total_difficulty: vec![],
+        })
+    }
+}
+
+/// Try from a Generalized AnyBlock enum
+impl TryFrom<&AnyBlock> for HeaderRecordWithNumber {
+    type Error = DecoderError;
+
+    fn try_from(block: &AnyBlock) -> Result<Self, Self::Error> {
+        match block {
+            AnyBlock::Evm(eth_block) | AnyBlock::Arb(eth_block) => {
+                HeaderRecordWithNumber::try_from(eth_block)
+            }
+            AnyBlock::Sol(sol_block) => HeaderRecordWithNumber::try_from(sol_block),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use crate::read_blocks_from_reader;
+
+    use super::*;
+
+    #[test]
+    fn test_versioned_bytes_round_trip() {
+        let file = File::open("tests/0000000000.dbin").unwrap();
+        let blocks = read_blocks_from_reader(BufReader::new(file), false.into()).unwrap();
+        let record = HeaderRecordWithNumber::try_from(&blocks[1]).unwrap();
+        assert_eq!(record.block_number, 1);
+
+        let bytes = record.to_versioned_bytes().unwrap();
+        assert_eq!(bytes[0], HEADER_RECORD_VERSION);
+        assert_eq!(
+            HeaderRecordWithNumber::from_versioned_bytes(&bytes).unwrap(),
+            record
+        );
+    }
+
+    #[test]
+    fn test_unsupported_record_version() {
+        let record = HeaderRecordWithNumber {
+            block_hash: vec![1; 32],
+            block_number: 42,
+            total_difficulty: vec![2],
+        };
+        let mut bytes = record.to_versioned_bytes().unwrap();
+        bytes[0] = HEADER_RECORD_VERSION + 1;
+
+        assert!(matches!(
+            HeaderRecordWithNumber::from_versioned_bytes(&bytes),
+            Err(DecoderError::UnsupportedRecordVersion(version)) if version == HEADER_RECORD_VERSION + 1
+        ));
+        assert!(matches!(
+            HeaderRecordWithNumber::from_versioned_bytes(&[]),
+            Err(DecoderError::BytesInvalid)
+        ));
+    }
+}
@@ -7,7 +7,9 @@
 mod dbin;
 mod decoder;
 mod error;
+mod header_record;
 
 pub use dbin::*;
 pub use decoder::*;
 pub use error::*;
+pub use header_record::*;
@@ -8,7 +8,7 @@
 
 use std::io::{self, Write};
 
-use firehose_protos::BstreamBlock;
+use firehose_protos::{BstreamBlock, EthBlock};
 use prost::Message;
 use tracing::warn;
 
@@ -67,6 +67,7 @@ fn type_url_for(content_type: &str) -> &str {
 /// Public encoder for producing DBIN-like streams.
 pub struct Encoder {
     config: EncoderConfig,
+    zstd_level: Option<i32>,
 }
 
 impl Encoder {
@@ -83,22 +84,43 @@ impl Encoder {
                 content_type: ct,
                 content_version,
             },
+            zstd_level: None,
         }
     }
 
     /// Create a V1 encoder with an arbitrary content type string.
+    ///
+    /// Panics if the content type is longer than `u16::MAX` bytes; see [`Encoder::try_new_v1`].
     pub fn new_v1(content_type: &str) -> Self {
-        let ct_bytes = content_type.as_bytes();
-        assert!(
-            ct_bytes.len() <= Self::MAX_CT_LEN,
-            "content_type must be <= {} bytes for V1",
-            Self::MAX_CT_LEN
-        );
-        Self {
+        Self::try_new_v1(content_type).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Create a V1 encoder, rejecting content types whose length does not fit the
+    /// two-byte length prefix of the V1 header.
+    pub fn try_new_v1(content_type: &str) -> io::Result<Self> {
+        let ct_len = content_type.len();
+        if ct_len > Self::MAX_CT_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "content_type is {ct_len} bytes, must be <= {} bytes for V1",
+                    Self::MAX_CT_LEN
+                ),
+            ));
+        }
+        Ok(Self {
             config: EncoderConfig::V1 {
                 content_type: content_type.to_string(),
             },
-        }
+            zstd_level: None,
+        })
+    }
+
+    /// Compress the whole output stream with zstd at the given level, producing files readable
+    /// with the decoder's `Compression::Zstd`.
+    pub fn with_zstd(mut self, level: i32) -> Self {
+        self.zstd_level = Some(level);
+        self
     }
 
     /// Serialize each item with `serialize`, optionally wrap it in a
@@ -117,7 +139,7 @@ impl Encoder {
     ///   if you emit a different block type.
     pub fn encode_with<I, T, W, S>(
         &self,
-        mut w: W,
+        w: W,
         items: I,
         frame_kind: FrameKind,
         mut serialize: S,
@@ -127,7 +149,6 @@ impl Encoder {
         W: Write,
         S: FnMut(T) -> Vec<u8>,
     {
-        self.write_header_io(&mut w)?;
         let header_ct = self.config.content_type_str()?;
         let ct_url = type_url_for(header_ct);
         if let FrameKind::Bstream = frame_kind {
@@ -138,23 +159,26 @@ impl Encoder {
                 );
             }
         }
-        for item in items {
-            let mut bytes = serialize(item);
-            if let FrameKind::Bstream = frame_kind {
-                let any = prost_wkt_types::Any {
-                    type_url: ct_url.to_string(),
-                    value: bytes,
-                };
-                bytes = BstreamBlock {
-                    payload: Some(any),
-                    ..Default::default()
+        self.write_stream_io(w, |w| {
+            self.write_header_io(w)?;
+            for item in items {
+                let mut bytes = serialize(item);
+                if let FrameKind::Bstream = frame_kind {
+                    let any = prost_wkt_types::Any {
+                        type_url: ct_url.to_string(),
+                        value: bytes,
+                    };
+                    bytes = BstreamBlock {
+                        payload: Some(any),
+                        ..Default::default()
+                    }
+                    .encode_to_vec();
                 }
-                .encode_to_vec();
-            }
 
-            self.write_frame_io(&mut w, &bytes)?;
-        }
-        Ok(())
+                self.write_frame_io(w, &bytes)?;
+            }
+            Ok(())
+        })
     }
 
     /// Encode Prost messages (e.g. [`firehose_protos::EthBlock`], `SolBlock`) as Bstream frames to any `Write`.
@@ -167,6 +191,24 @@ impl Encoder {
         self.encode_with(&mut w, blocks, FrameKind::Bstream, |m| m.encode_to_vec())
     }
 
+    /// Encode an Ethereum block as the bytes of a single Bstream frame.
+    ///
+    /// The block is prost-encoded into the `payload_buffer` of a [`BstreamBlock`], the layout of
+    /// legacy V0 Ethereum flat files. Pass the returned frames to
+    /// [`Encoder::encode_bytes_to_writer`] to produce a stream the decoder reads back.
+    #[allow(deprecated)]
+    pub fn encode_eth_block(block: &EthBlock) -> Vec<u8> {
+        let header = block.header.as_ref();
+        BstreamBlock {
+            number: block.number,
+            parent_num: block.number.saturating_sub(1),
+            timestamp: header.and_then(|header| header.timestamp.clone()),
+            payload_buffer: block.encode_to_vec(),
+            ..Default::default()
+        }
+        .encode_to_vec()
+    }
+
     /// Encode one SSZ value (e.g. BeaconState) as a single raw frame to any `Write`.
     pub fn encode_ssz_value_to_writer<W, T: ssz::Encode>(
         &self,
@@ -182,20 +224,39 @@ impl Encoder {
     }
 
     /// Encode already-prepared byte slices as raw frames to any `Write`.
-    pub fn encode_bytes_to_writer<I, B, W>(&self, mut w: W, frames: I) -> io::Result<()>
+    pub fn encode_bytes_to_writer<I, B, W>(&self, w: W, frames: I) -> io::Result<()>
     where
         I: IntoIterator<Item = B>,
         B: AsRef<[u8]>,
         W: Write,
     {
-        self.write_header_io(&mut w)?;
-        for b in frames {
-            self.write_frame_io(&mut w, b.as_ref())?;
+        self.write_stream_io(w, |w| {
+            self.write_header_io(w)?;
+            for b in frames {
+                self.write_frame_io(w, b.as_ref())?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs `write` against `w`, through a zstd encoder if compression is enabled.
+    fn write_stream_io<W, F>(&self, mut w: W, write: F) -> io::Result<()>
+    where
+        W: Write,
+        F: FnOnce(&mut dyn Write) -> io::Result<()>,
+    {
+        match self.zstd_level {
+            Some(level) => {
+                let mut zstd = zstd::Encoder::new(w, level)?;
+                write(&mut zstd)?;
+                zstd.finish()?;
+                Ok(())
+            }
+            None => write(&mut w),
         }
-        Ok(())
     }
 
-    fn write_header_io<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    fn write_header_io<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
         w.write_all(b"dbin")?;
         match &self.config {
             EncoderConfig::V0 {
@@ -209,14 +270,17 @@ impl Encoder {
             EncoderConfig::V1 { content_type } => {
                 w.write_all(&[1u8])?;
                 let ct = content_type.as_bytes();
-                w.write_all(&(ct.len() as u16).to_be_bytes())?;
+                let ct_len = u16::try_from(ct.len()).map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "content type too long for u16")
+                })?;
+                w.write_all(&ct_len.to_be_bytes())?;
                 w.write_all(ct)?;
             }
         }
         Ok(())
     }
 
-    fn write_frame_io<W: Write>(&self, w: &mut W, block: &[u8]) -> io::Result<()> {
+    fn write_frame_io<W: Write + ?Sized>(&self, w: &mut W, block: &[u8]) -> io::Result<()> {
         if block.len() > Self::MAX_FRAME {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
@@ -1,6 +1,9 @@
 // SPDX-FileCopyrightText: 2025- Semiotic AI, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::fs::File;
+
+use flat_files_decoder::{read_eth_blocks_from_reader, Compression, DbinFile, Version};
 use flat_files_encoder::{Encoder, FrameKind};
 
 #[test]
@@ -43,3 +46,64 @@ fn test_sol_block_encode_to_dbin_header() {
     let expected = b"type.googleapis.com/sf.solana.type.v1.Block";
     assert_eq!(ct, expected);
 }
+
+#[test]
+fn test_v0_content_version_round_trip() {
+    let enc = Encoder::new_v0("ETH", *b"01");
+
+    let mut dbin = Vec::new();
+    enc.encode_bytes_to_writer(&mut dbin, [b"test".as_slice()])
+        .unwrap();
+
+    let dbin_file = DbinFile::try_from_read(dbin.as_slice()).unwrap();
+    assert_eq!(dbin_file.version(), Version::V0);
+    assert_eq!(dbin_file.content_type(), "ETH");
+    assert_eq!(dbin_file.content_version(), Some("01"));
+}
+
+#[test]
+fn test_eth_blocks_encode_decode_round_trip() {
+    let file = File::open("../decoder/tests/0000000000.dbin").unwrap();
+    let blocks = read_eth_blocks_from_reader(file, Compression::None).unwrap();
+
+    let enc = Encoder::new_v0("ETH", *b"01");
+    let mut dbin = Vec::new();
+    enc.encode_bytes_to_writer(&mut dbin, blocks.iter().map(Encoder::encode_eth_block))
+        .unwrap();
+
+    let decoded = read_eth_blocks_from_reader(dbin.as_slice(), Compression::None).unwrap();
+    assert_eq!(decoded.len(), 100);
+    assert_eq!(decoded, blocks);
+}
+
+#[test]
+fn test_try_new_v1_content_type_length() {
+    let too_long = "a".repeat(u16::MAX as usize + 1);
+    let err = Encoder::try_new_v1(&too_long).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let content_type = "type.googleapis.com/sf.ethereum.type.v2.Block";
+    let enc = Encoder::try_new_v1(content_type).unwrap();
+    let mut dbin = Vec::new();
+    enc.encode_bytes_to_writer(&mut dbin, [b"test".as_slice()])
+        .unwrap();
+
+    let dbin_file = DbinFile::try_from_read(dbin.as_slice()).unwrap();
+    assert_eq!(dbin_file.version(), Version::V1);
+    assert_eq!(dbin_file.content_type(), content_type);
+}
+
+#[test]
+fn test_eth_blocks_zstd_round_trip() {
+    let file = File::open("../decoder/tests/0000000000.dbin").unwrap();
+    let blocks = read_eth_blocks_from_reader(file, Compression::None).unwrap();
+
+    let enc = Encoder::new_v0("ETH", *b"01").with_zstd(3);
+    let mut dbin = Vec::new();
+    enc.encode_bytes_to_writer(&mut dbin, blocks.iter().map(Encoder::encode_eth_block))
+        .unwrap();
+    assert!(!dbin.starts_with(b"dbin"));
+
+    let decoded = read_eth_blocks_from_reader(dbin.as_slice(), Compression::Zstd).unwrap();
+    assert_eq!(decoded, blocks);
+}
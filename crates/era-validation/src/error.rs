@@ -1,13 +1,16 @@
 // SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use alloy_primitives::B256;
 #[cfg(feature = "firehose")]
 use firehose_protos::ProtosError;
 use primitive_types::H256;
 
-use crate::types::{BlockNumber, EpochNumber};
 #[cfg(feature = "beacon")]
-use crate::types::{EraNumber, SlotNumber};
+use crate::types::EraNumber;
+#[cfg(any(feature = "beacon", feature = "solana"))]
+use crate::types::SlotNumber;
+use crate::types::{BlockNumber, EpochNumber};
 
 /// Unified era validation error type for all blockchain eras and chains
 #[derive(thiserror::Error, Debug)]
@@ -46,9 +49,23 @@ pub enum EraValidationError {
         blocks: Vec<BlockNumber>,
     },
 
+    #[error("total difficulty of block {block_number} is not the previous total difficulty plus the block difficulty")]
+    TotalDifficultyInconsistent {
+        /// Block number
+        block_number: BlockNumber,
+    },
+
     #[error("not all blocks are in the same epoch. epochs found: {0:?}")]
     InvalidBlockInEpoch(Vec<EpochNumber>),
 
+    #[error("epoch number {expected} does not match the epoch {actual} of its blocks")]
+    EpochNumberMismatch {
+        /// Epoch number recorded alongside the blocks
+        expected: EpochNumber,
+        /// Epoch the blocks belong to
+        actual: EpochNumber,
+    },
+
     #[error("block epoch {block_epoch} (block number {block_number}) could not be proven with provided epoch {epoch_number}.")]
     EpochNotMatchForHeader {
         /// Epoch number
@@ -74,6 +91,15 @@ pub enum EraValidationError {
     #[error("error validating inclusion proof")]
     ProofValidationFailure,
 
+    #[error("inclusion proof must be 488 bytes, found {0}")]
+    InvalidInclusionProofLength(usize),
+
+    #[error("block {0} is not in the post-merge, pre-capella range")]
+    NotPostMergePreCapella(BlockNumber),
+
+    #[error("beacon block does not carry the execution payload of block {0}")]
+    BeaconBlockMismatch(BlockNumber),
+
     // Header/Block errors
     #[cfg(feature = "firehose")]
     #[error("error decoding header from flat files: {0}")]
@@ -96,6 +122,32 @@ pub enum EraValidationError {
     // Accumulator errors
     #[error("era accumulator mismatch")]
     EraAccumulatorMismatch,
+
+    #[error("historical batches need the same number of block roots ({block_roots}) and state roots ({state_roots}), in full eras of 8192")]
+    HistoricalBatchInvalid {
+        /// Number of block roots provided
+        block_roots: usize,
+        /// Number of state roots provided
+        state_roots: usize,
+    },
+
+    // Proof bundle errors
+    #[error("proof bundle was created for accumulator {expected}, but verification uses {actual}")]
+    ProofBundleAccumulatorMismatch {
+        /// Accumulator root recorded in the bundle
+        expected: B256,
+        /// Accumulator root used for verification
+        actual: B256,
+    },
+
+    #[error("invalid proof bundle: {0}")]
+    InvalidProofBundle(String),
+
+    #[error("invalid proof bundle header: {0}")]
+    InvalidProofBundleHeader(#[source] alloy_rlp::Error),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 /// Ethereum pre-merge specific errors
@@ -116,6 +168,12 @@ pub enum EthereumPreMergeError {
         epoch: EpochNumber,
         max_epoch: EpochNumber,
     },
+
+    #[error("epoch {epoch} crosses the merge at block {merge_block}")]
+    EpochCrossesMerge {
+        epoch: EpochNumber,
+        merge_block: u64,
+    },
 }
 
 /// Common errors for Ethereum PoS eras (post-merge and post-Capella)
@@ -159,6 +217,9 @@ pub enum EthereumPostMergeError {
 pub enum EthereumPostCapellaError {
     #[error(transparent)]
     Common(#[from] EthereumPosEraError),
+
+    #[error("invalid post-capella inclusion proof: {0}")]
+    InvalidProof(#[source] anyhow::Error),
 }
 
 /// Solana specific errors
@@ -180,6 +241,22 @@ pub enum SolanaValidatorError {
         epoch: EpochNumber,
         max_epoch: EpochNumber,
     },
+
+    #[error("block at slot {slot} does not belong to era {era}")]
+    SlotOutsideEra { slot: SlotNumber, era: EpochNumber },
+
+    #[error("invalid era start: slot {0} is not a multiple of 432000")]
+    InvalidEraStart(SlotNumber),
+
+    #[error("block at slot {slot} does not extend the previous block: expected parent {expected_parent}, got {actual_parent}")]
+    BrokenHashChain {
+        slot: SlotNumber,
+        expected_parent: String,
+        actual_parent: String,
+    },
+
+    #[error("invalid block hash: {0}")]
+    InvalidBlockHash(String),
 }
 
 #[cfg(feature = "firehose")]
@@ -0,0 +1,356 @@
+// SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! portable bundles of inclusion proofs
+//!
+//! a [`ProofBundle`] packs a set of provable headers into a single file which can be handed to a
+//! third party and verified offline. the file layout is:
+//!
+//! | field              | size                                    |
+//! |--------------------|-----------------------------------------|
+//! | magic bytes `vepb` | 4 bytes                                 |
+//! | format version     | 1 byte                                  |
+//! | accumulator root   | 32 bytes                                |
+//! | header count       | 4 bytes, big-endian                     |
+//! | headers            | repeated for each header, see below     |
+//!
+//! each header is written as a 4 byte big-endian length prefix, the rlp encoded header, then the
+//! 15 proof hashes of 32 bytes each.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use alloy_consensus::Header;
+use alloy_primitives::{FixedBytes, B256};
+use alloy_rlp::Decodable;
+use tree_hash::TreeHash;
+use validation::PreMergeAccumulator;
+
+use crate::{
+    error::EraValidationError,
+    ethereum::proof::{
+        verify_inclusion_proof, HeaderValidatorBuilder, HeaderWithProof, InclusionProof, PROOF_SIZE,
+    },
+    types::BlockNumber,
+};
+
+const BUNDLE_MAGIC_BYTES: &[u8; 4] = b"vepb";
+const BUNDLE_VERSION: u8 = 1;
+/// upper bound on the length of an rlp encoded header, which is well under 1 KiB
+const MAX_HEADER_LENGTH: usize = 1024;
+
+/// A set of provable headers together with the root of the accumulator they prove against
+#[derive(Clone)]
+pub struct ProofBundle {
+    accumulator_root: B256,
+    headers: Vec<HeaderWithProof>,
+}
+
+impl ProofBundle {
+    /// Bundles headers whose proofs were generated against the default pre-merge accumulator
+    pub fn new(headers: Vec<HeaderWithProof>) -> Self {
+        Self::with_accumulator(&PreMergeAccumulator::default(), headers)
+    }
+
+    /// Bundles headers whose proofs were generated against the given pre-merge accumulator
+    pub fn with_accumulator(
+        pre_merge_acc: &PreMergeAccumulator,
+        headers: Vec<HeaderWithProof>,
+    ) -> Self {
+        Self {
+            accumulator_root: pre_merge_acc.tree_hash_root(),
+            headers,
+        }
+    }
+
+    /// Tree hash root identifying the accumulator the proofs were generated against
+    pub fn accumulator_root(&self) -> B256 {
+        self.accumulator_root
+    }
+
+    /// The provable headers in the bundle
+    pub fn headers(&self) -> &[HeaderWithProof] {
+        &self.headers
+    }
+
+    /// Verifies every header in the bundle against the default pre-merge accumulator
+    pub fn verify_all(&self) -> Result<(), EraValidationError> {
+        self.verify_all_with(PreMergeAccumulator::default())
+    }
+
+    /// Verifies every header in the bundle against the given pre-merge accumulator
+    ///
+    /// fails early if the accumulator is not the one the bundle was created for.
+    pub fn verify_all_with(
+        &self,
+        pre_merge_acc: PreMergeAccumulator,
+    ) -> Result<(), EraValidationError> {
+        let actual = pre_merge_acc.tree_hash_root();
+        if actual != self.accumulator_root {
+            return Err(EraValidationError::ProofBundleAccumulatorMismatch {
+                expected: self.accumulator_root,
+                actual,
+            });
+        }
+
+        let header_validator = HeaderValidatorBuilder::default()
+            .pre_merge_acc(pre_merge_acc)
+            .build();
+
+        for provable_header in &self.headers {
+            verify_inclusion_proof(&header_validator, provable_header.clone())?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the bundle to a file at `path`
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), EraValidationError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a bundle from a file at `path`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, EraValidationError> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the bundle to any [`Write`] sink
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), EraValidationError> {
+        writer.write_all(BUNDLE_MAGIC_BYTES)?;
+        writer.write_all(&[BUNDLE_VERSION])?;
+        writer.write_all(self.accumulator_root.as_slice())?;
+        writer.write_all(&length_prefix(self.headers.len())?)?;
+
+        for provable_header in &self.headers {
+            let encoded_header = alloy_rlp::encode(&provable_header.header);
+            writer.write_all(&length_prefix(encoded_header.len())?)?;
+            writer.write_all(&encoded_header)?;
+            for hash in &provable_header.proof.proof {
+                writer.write_all(hash.as_slice())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a bundle from any [`Read`] source
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, EraValidationError> {
+        let mut magic_bytes = [0u8; 4];
+        reader.read_exact(&mut magic_bytes)?;
+        if &magic_bytes != BUNDLE_MAGIC_BYTES {
+            return Err(EraValidationError::InvalidProofBundle(
+                "invalid magic bytes".to_string(),
+            ));
+        }
+
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != BUNDLE_VERSION {
+            return Err(EraValidationError::InvalidProofBundle(format!(
+                "unsupported version {}",
+                version[0]
+            )));
+        }
+
+        let accumulator_root = read_hash(&mut reader)?;
+        let count = read_length(&mut reader)?;
+
+        // the count is untrusted, so the headers are not preallocated
+        let mut headers = Vec::new();
+        for _ in 0..count {
+            let header_length = read_length(&mut reader)?;
+            if header_length > MAX_HEADER_LENGTH {
+                return Err(EraValidationError::InvalidProofBundle(format!(
+                    "header length {header_length} exceeds {MAX_HEADER_LENGTH} bytes"
+                )));
+            }
+            let mut encoded_header = vec![0u8; header_length];
+            reader.read_exact(&mut encoded_header)?;
+            let mut encoded_header = encoded_header.as_slice();
+            let header = Header::decode(&mut encoded_header)
+                .map_err(EraValidationError::InvalidProofBundleHeader)?;
+            if !encoded_header.is_empty() {
+                return Err(EraValidationError::InvalidProofBundle(format!(
+                    "{} unexpected bytes after header",
+                    encoded_header.len()
+                )));
+            }
+
+            let mut proof = [FixedBytes::<32>::ZERO; PROOF_SIZE];
+            for hash in proof.iter_mut() {
+                *hash = read_hash(&mut reader)?;
+            }
+
+            headers.push(HeaderWithProof {
+                proof: InclusionProof {
+                    block_number: BlockNumber(header.number),
+                    proof,
+                },
+                header,
+            });
+        }
+
+        Ok(Self {
+            accumulator_root,
+            headers,
+        })
+    }
+}
+
+fn length_prefix(length: usize) -> Result<[u8; 4], EraValidationError> {
+    u32::try_from(length)
+        .map(u32::to_be_bytes)
+        .map_err(|_| EraValidationError::InvalidProofBundle(format!("length {length} too large")))
+}
+
+fn read_length<R: Read>(reader: &mut R) -> Result<usize, EraValidationError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_be_bytes(bytes) as usize)
+}
+
+fn read_hash<R: Read>(reader: &mut R) -> Result<B256, EraValidationError> {
+    let mut bytes = [0u8; 32];
+    reader.read_exact(&mut bytes)?;
+    Ok(B256::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::{
+        proof::generate_inclusion_proof,
+        test_utils::{epoch_records, pre_merge_accumulator},
+        types::Epoch,
+    };
+
+    fn bundle() -> (ProofBundle, PreMergeAccumulator) {
+        let records = epoch_records(0);
+        let headers: Vec<Header> = [1, 4000, 8191]
+            .iter()
+            .map(|&number| records[number].full_header.clone().unwrap())
+            .collect();
+        let epoch = Epoch::try_from(records).unwrap();
+        let pre_merge_acc = pre_merge_accumulator(&[&epoch]);
+
+        let headers = headers
+            .into_iter()
+            .map(|header| {
+                generate_inclusion_proof(header.clone(), epoch.clone())
+                    .unwrap()
+                    .with_header(header)
+                    .unwrap()
+            })
+            .collect();
+
+        (
+            ProofBundle::with_accumulator(&pre_merge_acc, headers),
+            pre_merge_acc,
+        )
+    }
+
+    fn bundle_bytes(bundle: &ProofBundle) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bundle.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// encodes a bundle of one header, given as raw bytes, with an empty proof
+    fn single_header_bundle_bytes(encoded_header: &[u8]) -> Vec<u8> {
+        let mut bytes = BUNDLE_MAGIC_BYTES.to_vec();
+        bytes.push(BUNDLE_VERSION);
+        bytes.extend_from_slice(B256::ZERO.as_slice());
+        bytes.extend_from_slice(&1u32.to_be_bytes());
+        bytes.extend_from_slice(&length_prefix(encoded_header.len()).unwrap());
+        bytes.extend_from_slice(encoded_header);
+        bytes.extend_from_slice(&[0u8; PROOF_SIZE * 32]);
+        bytes
+    }
+
+    #[test]
+    fn round_trips_and_verifies() {
+        let (bundle, pre_merge_acc) = bundle();
+
+        let read = ProofBundle::read_from(bundle_bytes(&bundle).as_slice()).unwrap();
+        assert_eq!(read.accumulator_root(), bundle.accumulator_root());
+        assert_eq!(read.headers().len(), 3);
+        assert_eq!(read.headers()[2].header, bundle.headers()[2].header);
+        read.verify_all_with(pre_merge_acc).unwrap();
+
+        assert!(matches!(
+            read.verify_all_with(PreMergeAccumulator::default()),
+            Err(EraValidationError::ProofBundleAccumulatorMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_magic_bytes() {
+        let (bundle, _) = bundle();
+        let mut bytes = bundle_bytes(&bundle);
+        bytes[0] = b'x';
+
+        assert!(matches!(
+            ProofBundle::read_from(bytes.as_slice()),
+            Err(EraValidationError::InvalidProofBundle(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_bundle() {
+        let (bundle, _) = bundle();
+        let bytes = bundle_bytes(&bundle);
+
+        assert!(matches!(
+            ProofBundle::read_from(&bytes[..bytes.len() - 1]),
+            Err(EraValidationError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_oversized_lengths_without_allocating() {
+        let mut bytes = BUNDLE_MAGIC_BYTES.to_vec();
+        bytes.push(BUNDLE_VERSION);
+        bytes.extend_from_slice(B256::ZERO.as_slice());
+        bytes.extend_from_slice(&u32::MAX.to_be_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_be_bytes());
+
+        assert!(matches!(
+            ProofBundle::read_from(bytes.as_slice()),
+            Err(EraValidationError::InvalidProofBundle(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        let (bundle, _) = bundle();
+        let header = &bundle.headers()[0].header;
+
+        let read = ProofBundle::read_from(
+            single_header_bundle_bytes(&alloy_rlp::encode(header)).as_slice(),
+        )
+        .unwrap();
+        assert_eq!(&read.headers()[0].header, header);
+
+        let error = ProofBundle::read_from(single_header_bundle_bytes(&[0x01]).as_slice())
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            EraValidationError::InvalidProofBundleHeader(_)
+        ));
+        assert!(std::error::Error::source(&error).is_some());
+
+        let mut trailing = alloy_rlp::encode(header);
+        trailing.push(0);
+        assert!(matches!(
+            ProofBundle::read_from(single_header_bundle_bytes(&trailing).as_slice()),
+            Err(EraValidationError::InvalidProofBundle(_))
+        ));
+    }
+}
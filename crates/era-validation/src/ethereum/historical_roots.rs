@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! construction of the post-merge historical roots accumulator
+//!
+//! each entry of the beacon state's `historical_roots` is the tree hash root of a
+//! `HistoricalBatch { block_roots, state_roots }` covering one era of 8192 slots. the
+//! [`HistoricalRootsAccumulator`] used to validate post-merge, pre-Capella headers can therefore
+//! be rebuilt from the beacon block roots and beacon state roots of every era, starting at
+//! genesis. block roots alone are not enough: the state roots root is a sibling in every
+//! `HistoricalRootsBlockProof`.
+
+use alloy_primitives::B256;
+use ethportal_api::consensus::beacon_state::HistoricalRoots;
+use primitive_types::H256;
+use validation::{historical_roots::HistoricalRootsAccumulator, merkle::proof::MerkleTree};
+
+use crate::{error::EraValidationError, ethereum::types::MAX_EPOCH_SIZE};
+
+/// depth of the merkle tree over the 8192 roots of an era
+pub(crate) const ERA_TREE_DEPTH: usize = 13;
+
+/// computes the tree hash root of a `HistoricalBatch` for a single era
+///
+/// `block_roots` and `state_roots` must each contain exactly the 8192 roots of the era.
+pub fn historical_batch_root(
+    block_roots: &[H256],
+    state_roots: &[H256],
+) -> Result<H256, EraValidationError> {
+    if block_roots.len() != MAX_EPOCH_SIZE || state_roots.len() != MAX_EPOCH_SIZE {
+        return Err(EraValidationError::HistoricalBatchInvalid {
+            block_roots: block_roots.len(),
+            state_roots: state_roots.len(),
+        });
+    }
+
+    let block_roots_root = roots_tree_hash_root(block_roots);
+    let state_roots_root = roots_tree_hash_root(state_roots);
+    let batch_root = MerkleTree::create(&[block_roots_root, state_roots_root], 1).hash();
+
+    Ok(H256::from(batch_root.0))
+}
+
+/// builds a [`HistoricalRootsAccumulator`] from the beacon block roots and state roots of
+/// consecutive eras, starting at genesis
+///
+/// both slices must have the same length, which must be a multiple of 8192. the nth chunk of
+/// 8192 roots yields the nth historical root.
+pub fn historical_roots_accumulator_from_roots(
+    block_roots: &[H256],
+    state_roots: &[H256],
+) -> Result<HistoricalRootsAccumulator, EraValidationError> {
+    if block_roots.is_empty()
+        || block_roots.len() != state_roots.len()
+        || block_roots.len() % MAX_EPOCH_SIZE != 0
+    {
+        return Err(EraValidationError::HistoricalBatchInvalid {
+            block_roots: block_roots.len(),
+            state_roots: state_roots.len(),
+        });
+    }
+
+    let historical_roots = block_roots
+        .chunks(MAX_EPOCH_SIZE)
+        .zip(state_roots.chunks(MAX_EPOCH_SIZE))
+        .map(|(block_roots, state_roots)| {
+            historical_batch_root(block_roots, state_roots).map(|root| B256::from(root.0))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let historical_roots = HistoricalRoots::new(historical_roots).map_err(|_| {
+        EraValidationError::HistoricalBatchInvalid {
+            block_roots: block_roots.len(),
+            state_roots: state_roots.len(),
+        }
+    })?;
+
+    Ok(HistoricalRootsAccumulator { historical_roots })
+}
+
+pub(crate) fn roots_tree_hash_root(roots: &[H256]) -> B256 {
+    let leaves: Vec<B256> = roots.iter().map(|root| B256::from(root.0)).collect();
+    MerkleTree::create(&leaves, ERA_TREE_DEPTH).hash()
+}
@@ -3,27 +3,43 @@
 
 //! ethereum block era validation across all eras
 
+pub mod bundle;
 #[cfg(feature = "beacon")]
 mod common;
+pub mod historical_roots;
 #[cfg(feature = "beacon")]
 pub mod post_capella;
 #[cfg(feature = "beacon")]
 pub mod post_merge;
+#[cfg(feature = "beacon")]
+pub mod post_merge_proof;
 pub mod pre_merge;
 pub mod proof;
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub mod types;
 
 // re-export public types
+pub use bundle::ProofBundle;
+pub use historical_roots::{historical_batch_root, historical_roots_accumulator_from_roots};
 #[cfg(feature = "beacon")]
-pub use post_capella::EthereumPostCapellaValidator;
+pub use post_capella::{verify_post_capella_proof, EthereumPostCapellaValidator};
 #[cfg(feature = "beacon")]
 pub use post_merge::EthereumPostMergeValidator;
+#[cfg(feature = "beacon")]
+pub use post_merge_proof::{
+    generate_post_merge_inclusion_proof, verify_post_merge_inclusion_proof, HistoricalRootsContext,
+};
 pub use pre_merge::EthereumPreMergeValidator;
 pub use proof::{
     generate_inclusion_proof, generate_inclusion_proofs, verify_inclusion_proof,
-    verify_inclusion_proofs, HeaderWithProof, InclusionProof,
+    verify_inclusion_proofs, verify_inclusion_proofs_detailed, HeaderValidatorBuilder,
+    HeaderWithProof, InclusionProof, StreamingVerifier, VerificationReport, INCLUSION_PROOF_LENGTH,
+};
+pub use types::{
+    block_range_of_epoch, epoch_of_block, Epoch, ExtHeaderRecord, FINAL_EPOCH, MAX_EPOCH_SIZE,
+    MERGE_BLOCK,
 };
-pub use types::{Epoch, ExtHeaderRecord, FINAL_EPOCH, MAX_EPOCH_SIZE, MERGE_BLOCK};
 
 // re-export external types for convenience
 pub use ethportal_api::types::execution::accumulator::EpochAccumulator;
@@ -7,11 +7,16 @@ use crate::{
     traits::EraValidationContext,
     types::{EraNumber, SlotNumber},
 };
+use alloy_consensus::Header;
 use alloy_primitives::FixedBytes;
+use ethportal_api::consensus::historical_summaries::HistoricalSummaries;
 use merkle_proof::MerkleTree;
 use primitive_types::H256;
 use types::{BeaconBlock, MainnetEthSpec};
-use validation::constants::CAPELLA_FORK_EPOCH;
+use validation::{
+    constants::CAPELLA_FORK_EPOCH,
+    header_validator::{HeaderValidator, PostCapellaProof},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EthereumBlockSummaryRoots(pub Vec<H256>);
@@ -46,6 +51,26 @@ impl EthereumPostCapellaValidator {
     }
 }
 
+/// verifies that a post-capella header is included in the canonical chain
+///
+/// the proof links the header hash to a beacon block root, and that beacon block root to the
+/// historical summary of its era. capella and deneb proofs are both supported, the proof variant
+/// must match the era of the header's block number.
+pub fn verify_post_capella_proof(
+    header: Header,
+    proof: PostCapellaProof<'_>,
+    historical_summaries: &HistoricalSummaries,
+) -> Result<(), EthereumPostCapellaError> {
+    HeaderValidator::default()
+        .verify_post_capella_header(
+            header.number,
+            header.hash_slow(),
+            proof,
+            historical_summaries,
+        )
+        .map_err(EthereumPostCapellaError::InvalidProof)
+}
+
 impl EraValidationContext for EthereumBlockSummaryRoots {
     type EraInput = (Vec<Option<H256>>, Vec<BeaconBlock<MainnetEthSpec>>);
     type Error = EthereumPostCapellaError;
@@ -142,3 +167,118 @@ impl EraValidationContext for EthereumBlockSummaryRoots {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ethportal_api::{
+        consensus::historical_summaries::HistoricalSummary,
+        types::execution::header_with_proof::BlockProofHistoricalSummariesDeneb,
+    };
+    use validation::{
+        constants::{DENEB_BLOCK_NUMBER, SLOTS_PER_EPOCH},
+        merkle::proof::merkle_root_from_branch,
+        post_capella_types::EXECUTION_BLOCK_GENERALIZED_INDEX,
+    };
+
+    use super::*;
+
+    /// a deneb proof for `header` at the given slot, with the historical summaries it proves
+    /// against
+    fn deneb_proof(
+        header: &Header,
+        slot: u64,
+    ) -> (BlockProofHistoricalSummariesDeneb, HistoricalSummaries) {
+        let execution_block_proof: Vec<B256> = (0..12).map(B256::repeat_byte).collect();
+        let beacon_block_root = merkle_root_from_branch(
+            header.hash_slow(),
+            &execution_block_proof,
+            execution_block_proof.len(),
+            EXECUTION_BLOCK_GENERALIZED_INDEX.as_usize(),
+        );
+
+        let beacon_block_proof: Vec<B256> = (12..25).map(B256::repeat_byte).collect();
+        let block_summary_root = merkle_root_from_branch(
+            beacon_block_root,
+            &beacon_block_proof,
+            beacon_block_proof.len(),
+            MAX_EPOCH_SIZE + (slot as usize % MAX_EPOCH_SIZE),
+        );
+
+        let capella_start_slot = CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH;
+        let summary_index = (slot - capella_start_slot) as usize / MAX_EPOCH_SIZE;
+        let mut historical_summaries = vec![
+            HistoricalSummary {
+                block_summary_root: B256::ZERO,
+                state_summary_root: B256::ZERO,
+            };
+            summary_index + 1
+        ];
+        historical_summaries[summary_index].block_summary_root = block_summary_root;
+
+        let proof = BlockProofHistoricalSummariesDeneb {
+            beacon_block_root,
+            execution_block_proof: execution_block_proof.into(),
+            beacon_block_proof: beacon_block_proof.into(),
+            slot,
+        };
+        (proof, historical_summaries.into())
+    }
+
+    fn deneb_slot() -> u64 {
+        CAPELLA_FORK_EPOCH * SLOTS_PER_EPOCH + 2 * MAX_EPOCH_SIZE as u64 + 5
+    }
+
+    #[test]
+    fn verifies_deneb_proof() {
+        let header = Header {
+            number: DENEB_BLOCK_NUMBER + 1,
+            ..Default::default()
+        };
+        let (proof, historical_summaries) = deneb_proof(&header, deneb_slot());
+
+        verify_post_capella_proof(
+            header,
+            PostCapellaProof::Deneb(&proof),
+            &historical_summaries,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn rejects_deneb_proof_for_other_slot() {
+        let header = Header {
+            number: DENEB_BLOCK_NUMBER + 1,
+            ..Default::default()
+        };
+        let (mut proof, historical_summaries) = deneb_proof(&header, deneb_slot());
+        proof.slot += 1;
+
+        let error = verify_post_capella_proof(
+            header,
+            PostCapellaProof::Deneb(&proof),
+            &historical_summaries,
+        )
+        .unwrap_err();
+        assert!(matches!(error, EthereumPostCapellaError::InvalidProof(_)));
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn rejects_deneb_proof_for_capella_header() {
+        let header = Header {
+            number: DENEB_BLOCK_NUMBER - 1,
+            ..Default::default()
+        };
+        let (proof, historical_summaries) = deneb_proof(&header, deneb_slot());
+
+        assert!(matches!(
+            verify_post_capella_proof(
+                header,
+                PostCapellaProof::Deneb(&proof),
+                &historical_summaries,
+            ),
+            Err(EthereumPostCapellaError::InvalidProof(_))
+        ));
+    }
+}
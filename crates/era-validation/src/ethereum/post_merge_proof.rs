@@ -0,0 +1,317 @@
+// SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! inclusion proofs for post-merge, pre-capella headers
+//!
+//! between the merge and capella, execution headers are proven against the beacon state's
+//! `historical_roots`. a [`BlockProofHistoricalRoots`] chains two merkle branches: one from the
+//! execution block hash to the root of the bellatrix beacon block carrying it, and one from that
+//! beacon block root to the root of the era's `HistoricalBatch`, which is the historical root.
+
+use alloy_consensus::Header;
+use alloy_primitives::B256;
+use ethportal_api::types::execution::header_with_proof::{
+    BlockHeaderProof, BlockProofHistoricalRoots, HeaderWithProof as PortalHeaderWithProof,
+};
+use primitive_types::H256;
+use tree_hash::TreeHash;
+use types::{BeaconBlock, BeaconBlockBellatrix, MainnetEthSpec};
+use validation::{
+    constants::{MERGE_BLOCK_NUMBER, SHANGHAI_BLOCK_NUMBER},
+    header_validator::HeaderValidator,
+    merkle::proof::{merkle_root_from_branch, MerkleTree},
+    post_capella_types::EXECUTION_BLOCK_GENERALIZED_INDEX,
+};
+
+use crate::{
+    error::EraValidationError,
+    ethereum::{
+        historical_roots::{roots_tree_hash_root, ERA_TREE_DEPTH},
+        types::MAX_EPOCH_SIZE,
+    },
+    types::BlockNumber,
+};
+
+/// index of `block_hash` among the fields of a bellatrix `ExecutionPayload`
+const PAYLOAD_BLOCK_HASH_INDEX: usize = 12;
+/// index of `execution_payload` among the fields of a bellatrix `BeaconBlockBody`
+const BODY_EXECUTION_PAYLOAD_INDEX: usize = 9;
+/// index of `body` among the fields of a `BeaconBlock`
+const BLOCK_BODY_INDEX: usize = 4;
+/// number of hashes in the execution block proof of a bellatrix beacon block
+const EXECUTION_BLOCK_PROOF_DEPTH: usize = 11;
+
+/// beacon chain data needed to prove a post-merge, pre-capella execution header
+pub struct HistoricalRootsContext<'a> {
+    /// the bellatrix beacon block whose execution payload is the header to prove
+    pub beacon_block: &'a BeaconBlock<MainnetEthSpec>,
+    /// the 8192 beacon block roots of the era containing the beacon block
+    pub block_roots: &'a [H256],
+    /// the 8192 beacon state roots of the same era
+    pub state_roots: &'a [H256],
+}
+
+/// generates a [`BlockProofHistoricalRoots`] for a post-merge, pre-capella header
+///
+/// the header must be the execution payload of the context's beacon block, and the beacon block
+/// root must be found at its slot in the era's block roots. the resulting proof is verified
+/// against the historical roots by [`verify_post_merge_inclusion_proof`].
+pub fn generate_post_merge_inclusion_proof(
+    header: &Header,
+    context: &HistoricalRootsContext,
+) -> Result<BlockProofHistoricalRoots, EraValidationError> {
+    let block_number = BlockNumber(header.number);
+    if header.number <= MERGE_BLOCK_NUMBER || header.number >= SHANGHAI_BLOCK_NUMBER {
+        return Err(EraValidationError::NotPostMergePreCapella(block_number));
+    }
+
+    let BeaconBlock::Bellatrix(beacon_block) = context.beacon_block else {
+        return Err(EraValidationError::BeaconBlockMismatch(block_number));
+    };
+
+    let header_hash = header.hash_slow();
+    let payload = &beacon_block.body.execution_payload.execution_payload;
+    if B256::from(payload.block_hash.0 .0) != header_hash {
+        return Err(EraValidationError::BeaconBlockMismatch(block_number));
+    }
+
+    let beacon_block_root = root(beacon_block);
+    let execution_block_proof = execution_block_proof(beacon_block)?;
+    if merkle_root_from_branch(
+        header_hash,
+        &execution_block_proof,
+        EXECUTION_BLOCK_PROOF_DEPTH,
+        EXECUTION_BLOCK_GENERALIZED_INDEX.as_usize(),
+    ) != beacon_block_root
+    {
+        return Err(EraValidationError::ProofGenerationFailure);
+    }
+
+    if context.block_roots.len() != MAX_EPOCH_SIZE || context.state_roots.len() != MAX_EPOCH_SIZE {
+        return Err(EraValidationError::HistoricalBatchInvalid {
+            block_roots: context.block_roots.len(),
+            state_roots: context.state_roots.len(),
+        });
+    }
+
+    let slot = beacon_block.slot.as_u64();
+    let block_root_index = (slot % MAX_EPOCH_SIZE as u64) as usize;
+    let block_roots: Vec<B256> = context
+        .block_roots
+        .iter()
+        .map(|root| B256::from(root.0))
+        .collect();
+    if block_roots[block_root_index] != beacon_block_root {
+        return Err(EraValidationError::BeaconBlockMismatch(block_number));
+    }
+
+    // the block roots are the left child of the `HistoricalBatch`, so the last sibling on the
+    // way to the historical root is the root of the state roots.
+    let (_, mut beacon_block_proof) = MerkleTree::create(&block_roots, ERA_TREE_DEPTH)
+        .generate_proof(block_root_index, ERA_TREE_DEPTH)
+        .map_err(|_| EraValidationError::ProofGenerationFailure)?;
+    beacon_block_proof.push(roots_tree_hash_root(context.state_roots));
+
+    Ok(BlockProofHistoricalRoots {
+        beacon_block_proof: beacon_block_proof.into(),
+        beacon_block_root,
+        execution_block_proof: execution_block_proof.into(),
+        slot,
+    })
+}
+
+/// verifies a post-merge, pre-capella header against the historical roots of the header
+/// validator
+pub fn verify_post_merge_inclusion_proof(
+    header_validator: &HeaderValidator,
+    header: Header,
+    proof: BlockProofHistoricalRoots,
+) -> Result<(), EraValidationError> {
+    let hwp = PortalHeaderWithProof {
+        header,
+        proof: BlockHeaderProof::HistoricalRoots(proof),
+    };
+
+    header_validator
+        .validate_header_with_proof(&hwp)
+        .map_err(|_| EraValidationError::ProofValidationFailure)
+}
+
+/// builds the branch from the execution block hash up to the beacon block root, following
+/// `BeaconBlock.body.execution_payload.block_hash`
+fn execution_block_proof(
+    beacon_block: &BeaconBlockBellatrix<MainnetEthSpec>,
+) -> Result<Vec<B256>, EraValidationError> {
+    let body = &beacon_block.body;
+    let payload = &body.execution_payload.execution_payload;
+
+    let payload_fields = [
+        root(&payload.parent_hash),
+        root(&payload.fee_recipient),
+        root(&payload.state_root),
+        root(&payload.receipts_root),
+        root(&payload.logs_bloom),
+        root(&payload.prev_randao),
+        root(&payload.block_number),
+        root(&payload.gas_limit),
+        root(&payload.gas_used),
+        root(&payload.timestamp),
+        root(&payload.extra_data),
+        root(&payload.base_fee_per_gas),
+        root(&payload.block_hash),
+        root(&payload.transactions),
+    ];
+    let body_fields = [
+        root(&body.randao_reveal),
+        root(&body.eth1_data),
+        root(&body.graffiti),
+        root(&body.proposer_slashings),
+        root(&body.attester_slashings),
+        root(&body.attestations),
+        root(&body.deposits),
+        root(&body.voluntary_exits),
+        root(&body.sync_aggregate),
+        root(payload),
+    ];
+    let block_fields = [
+        root(&beacon_block.slot),
+        root(&beacon_block.proposer_index),
+        root(&beacon_block.parent_root),
+        root(&beacon_block.state_root),
+        root(body),
+    ];
+
+    let mut proof = container_field_proof(&payload_fields, PAYLOAD_BLOCK_HASH_INDEX)?;
+    proof.extend(container_field_proof(
+        &body_fields,
+        BODY_EXECUTION_PAYLOAD_INDEX,
+    )?);
+    proof.extend(container_field_proof(&block_fields, BLOCK_BODY_INDEX)?);
+
+    Ok(proof)
+}
+
+/// proves the field at `index` of an ssz container, given the roots of all its fields
+fn container_field_proof(fields: &[B256], index: usize) -> Result<Vec<B256>, EraValidationError> {
+    let depth = fields.len().next_power_of_two().trailing_zeros() as usize;
+    MerkleTree::create(fields, depth)
+        .generate_proof(index, depth)
+        .map(|(_, proof)| proof)
+        .map_err(|_| EraValidationError::ProofGenerationFailure)
+}
+
+fn root<T: TreeHash>(value: &T) -> B256 {
+    B256::from(value.tree_hash_root().0)
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{ChainSpec, EmptyBlock, ExecutionBlockHash, Hash256, Slot};
+
+    use super::*;
+    use crate::ethereum::{
+        historical_roots::historical_roots_accumulator_from_roots, proof::HeaderValidatorBuilder,
+    };
+
+    /// a synthetic bellatrix era whose block at `slot` carries `header` as its payload
+    struct Era {
+        beacon_block: BeaconBlock<MainnetEthSpec>,
+        block_roots: Vec<H256>,
+        state_roots: Vec<H256>,
+    }
+
+    fn era(header: &Header, slot: u64) -> Era {
+        let mut beacon_block = BeaconBlockBellatrix::<MainnetEthSpec>::empty(&ChainSpec::mainnet());
+        beacon_block.slot = Slot::new(slot);
+        beacon_block
+            .body
+            .execution_payload
+            .execution_payload
+            .block_hash = ExecutionBlockHash::from_root(Hash256::from(header.hash_slow().0));
+
+        let mut block_roots: Vec<H256> = (0..MAX_EPOCH_SIZE as u64)
+            .map(H256::from_low_u64_be)
+            .collect();
+        block_roots[slot as usize % MAX_EPOCH_SIZE] = H256::from(root(&beacon_block).0);
+        let state_roots = (0..MAX_EPOCH_SIZE as u64)
+            .map(|i| H256::from_low_u64_be(MAX_EPOCH_SIZE as u64 + i))
+            .collect();
+
+        Era {
+            beacon_block: BeaconBlock::Bellatrix(beacon_block),
+            block_roots,
+            state_roots,
+        }
+    }
+
+    fn context(era: &Era) -> HistoricalRootsContext<'_> {
+        HistoricalRootsContext {
+            beacon_block: &era.beacon_block,
+            block_roots: &era.block_roots,
+            state_roots: &era.state_roots,
+        }
+    }
+
+    fn header() -> Header {
+        Header {
+            number: MERGE_BLOCK_NUMBER + 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn generated_proof_verifies_against_historical_roots() {
+        let header = header();
+        let era = era(&header, 100);
+        let header_validator = HeaderValidatorBuilder::default()
+            .historical_roots_acc(
+                historical_roots_accumulator_from_roots(&era.block_roots, &era.state_roots)
+                    .unwrap(),
+            )
+            .build();
+
+        let proof = generate_post_merge_inclusion_proof(&header, &context(&era)).unwrap();
+        assert_eq!(
+            proof.execution_block_proof.len(),
+            EXECUTION_BLOCK_PROOF_DEPTH
+        );
+        assert_eq!(proof.beacon_block_proof.len(), ERA_TREE_DEPTH + 1);
+        verify_post_merge_inclusion_proof(&header_validator, header.clone(), proof.clone())
+            .unwrap();
+
+        let mut tampered = proof;
+        tampered.slot += 1;
+        assert!(matches!(
+            verify_post_merge_inclusion_proof(&header_validator, header, tampered),
+            Err(EraValidationError::ProofValidationFailure)
+        ));
+    }
+
+    #[test]
+    fn rejects_header_not_in_beacon_block() {
+        let era = era(&header(), 100);
+        let other = Header {
+            gas_limit: 1,
+            ..header()
+        };
+
+        assert!(matches!(
+            generate_post_merge_inclusion_proof(&other, &context(&era)),
+            Err(EraValidationError::BeaconBlockMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_pre_merge_header() {
+        let era = era(&header(), 100);
+        let pre_merge = Header {
+            number: MERGE_BLOCK_NUMBER,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            generate_post_merge_inclusion_proof(&pre_merge, &context(&era)),
+            Err(EraValidationError::NotPostMergePreCapella(_))
+        ));
+    }
+}
@@ -8,7 +8,7 @@ use validation::{HistoricalEpochRoots, PreMergeAccumulator};
 
 use crate::{
     error::{EraValidationError, EthereumPreMergeError},
-    ethereum::types::{Epoch, FINAL_EPOCH},
+    ethereum::types::{block_range_of_epoch, Epoch, FINAL_EPOCH, MERGE_BLOCK},
     traits::EraValidationContext,
     types::EpochNumber,
 };
@@ -69,6 +69,11 @@ impl EthereumPreMergeValidator {
     ///
     /// for block post merge, the sync-committee should be used to validate block headers
     /// in the canonical blockchain. so this function is not useful for those.
+    ///
+    /// the final pre-merge epoch of the header accumulator is truncated at [`MERGE_BLOCK`], so it
+    /// commits to fewer than 8192 blocks. an [`Epoch`] always holds 8192 blocks, so an epoch
+    /// whose block range crosses the merge block is rejected with
+    /// [`EthereumPreMergeError::EpochCrossesMerge`].
     pub fn validate_single_epoch(
         &self,
         epoch: &Epoch,
@@ -77,10 +82,16 @@ impl EthereumPreMergeValidator {
             return Err(EraValidationError::EpochPostMerge(epoch.number()));
         }
 
-        let header_records: Vec<_> = epoch.iter().cloned().collect();
-        let epoch_accumulator = EpochAccumulator::from(header_records);
+        if block_range_of_epoch(epoch.number().into()).contains(&MERGE_BLOCK) {
+            return Err(EraValidationError::EthereumPreMerge(
+                EthereumPreMergeError::EpochCrossesMerge {
+                    epoch: epoch.number(),
+                    merge_block: MERGE_BLOCK,
+                },
+            ));
+        }
 
-        let root = epoch_accumulator.tree_hash_root();
+        let root = epoch.accumulator_root();
 
         let epoch_idx = usize::from(epoch.number());
         if epoch_idx >= self.historical_roots.len() {
@@ -147,3 +158,45 @@ impl EraValidationContext for HistoricalEpochRoots {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::test_utils::{epoch_records, pre_merge_accumulator};
+
+    #[test]
+    fn validates_epoch_against_its_root() {
+        let epoch = Epoch::try_from(epoch_records(0)).unwrap();
+        let validator = EthereumPreMergeValidator::from(pre_merge_accumulator(&[&epoch]));
+
+        assert_eq!(
+            validator.validate_single_epoch(&epoch).unwrap(),
+            epoch.accumulator_root()
+        );
+    }
+
+    #[test]
+    fn rejects_epoch_crossing_the_merge() {
+        let epoch = Epoch::try_from(epoch_records(FINAL_EPOCH as u64)).unwrap();
+        let range = block_range_of_epoch(FINAL_EPOCH as u64);
+        assert!(range.contains(&(MERGE_BLOCK - 1)) && range.contains(&MERGE_BLOCK));
+
+        let result = EthereumPreMergeValidator::default().validate_single_epoch(&epoch);
+        assert!(matches!(
+            result,
+            Err(EraValidationError::EthereumPreMerge(
+                EthereumPreMergeError::EpochCrossesMerge { epoch, merge_block: MERGE_BLOCK }
+            )) if epoch == EpochNumber(FINAL_EPOCH as u64)
+        ));
+    }
+
+    #[test]
+    fn rejects_post_merge_epoch() {
+        let epoch = Epoch::try_from(epoch_records(FINAL_EPOCH as u64 + 1)).unwrap();
+
+        assert!(matches!(
+            EthereumPreMergeValidator::default().validate_single_epoch(&epoch),
+            Err(EraValidationError::EpochPostMerge(_))
+        ));
+    }
+}
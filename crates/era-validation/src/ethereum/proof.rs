@@ -17,21 +17,58 @@ use ethportal_api::types::execution::{
         HeaderWithProof as PortalHeaderWithProof,
     },
 };
+use serde::{Deserialize, Serialize};
 use validation::{
     header_validator::HeaderValidator, historical_roots::HistoricalRootsAccumulator,
     PreMergeAccumulator,
 };
 
-const PROOF_SIZE: usize = 15;
+pub(crate) const PROOF_SIZE: usize = 15;
+
+/// Length in bytes of an [`InclusionProof`] encoded with [`InclusionProof::to_bytes`]
+pub const INCLUSION_PROOF_LENGTH: usize = PROOF_SIZE * 32 + 8;
 
 /// A proof that contains the block number
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct InclusionProof {
-    block_number: BlockNumber,
-    proof: [FixedBytes<32>; PROOF_SIZE],
+    pub(crate) block_number: BlockNumber,
+    pub(crate) proof: [FixedBytes<32>; PROOF_SIZE],
 }
 
 impl InclusionProof {
+    /// Encodes the proof as its 15 proof hashes of 32 bytes each, followed by the block number
+    /// as 8 big-endian bytes
+    pub fn to_bytes(&self) -> [u8; INCLUSION_PROOF_LENGTH] {
+        let mut bytes = [0u8; INCLUSION_PROOF_LENGTH];
+        for (chunk, hash) in bytes.chunks_exact_mut(32).zip(&self.proof) {
+            chunk.copy_from_slice(hash.as_slice());
+        }
+        bytes[PROOF_SIZE * 32..].copy_from_slice(&self.block_number.0.to_be_bytes());
+        bytes
+    }
+
+    /// Decodes a proof encoded with [`InclusionProof::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EraValidationError> {
+        if bytes.len() != INCLUSION_PROOF_LENGTH {
+            return Err(EraValidationError::InvalidInclusionProofLength(bytes.len()));
+        }
+
+        let (proof_bytes, block_number_bytes) = bytes.split_at(PROOF_SIZE * 32);
+
+        let mut proof = [FixedBytes::<32>::ZERO; PROOF_SIZE];
+        for (hash, chunk) in proof.iter_mut().zip(proof_bytes.chunks_exact(32)) {
+            *hash = FixedBytes::from_slice(chunk);
+        }
+
+        let mut block_number = [0u8; 8];
+        block_number.copy_from_slice(block_number_bytes);
+
+        Ok(Self {
+            block_number: BlockNumber(u64::from_be_bytes(block_number)),
+            proof,
+        })
+    }
+
     /// Takes a header and turns the proof into a provable header
     pub fn with_header(self, header: Header) -> Result<HeaderWithProof, EraValidationError> {
         if self.block_number.0 != header.number {
@@ -122,15 +159,8 @@ impl InclusionProof {
 ///     .filter_map(|ext| ext.full_header.as_ref().cloned())
 ///     .collect();
 ///
-/// // separate headers by epoch
-/// let (epoch0_headers, epoch1_headers): (Vec<_>, Vec<_>) = all_headers
-///     .into_iter()
-///     .partition(|h| h.block_number < 8192);
-///
-/// // create epochs
-/// let epoch0: Epoch = epoch0_headers.try_into()?;
-/// let epoch1: Epoch = epoch1_headers.try_into()?;
-/// let epochs = vec![epoch0, epoch1];
+/// // separate headers by epoch and create epochs
+/// let epochs = Epoch::split_into_epochs(all_headers)?;
 ///
 /// // generate proofs for all selected headers
 /// let proofs = generate_inclusion_proofs(epochs, headers_to_prove.clone())?;
@@ -333,12 +363,7 @@ pub fn verify_inclusion_proofs(
     header_proofs: Vec<HeaderWithProof>,
     historical_summaries: Option<HistoricalSummaries>,
 ) -> Result<(), EraValidationError> {
-    let pre_merge_acc = pre_merge_accumulator_file.unwrap_or_default();
-    let header_validator = HeaderValidator {
-        pre_merge_acc,
-        historical_roots_acc: HistoricalRootsAccumulator::default(),
-        historical_summaries,
-    };
+    let header_validator = header_validator(pre_merge_accumulator_file, historical_summaries);
 
     for provable_header in header_proofs {
         verify_inclusion_proof(&header_validator, provable_header)?;
@@ -347,10 +372,100 @@ pub fn verify_inclusion_proofs(
     Ok(())
 }
 
+/// Verifies a list of provable headers, reporting the outcome for each of them
+///
+/// Unlike [`verify_inclusion_proofs`], verification does not stop at the first failing header.
+/// Every header is verified and returned with its block number, in the order they were provided,
+/// so the failing headers of a large set can be identified.
+///
+/// # Arguments
+///
+/// * `pre_merge_accumulator_file` - An optional [`PreMergeAccumulator`] containing
+///   historical epoch accumulator roots. Pass `None` to use the default embedded accumulator.
+/// * `header_proofs` - A [`Vec<HeaderWithProof>`] containing headers and their inclusion proofs
+/// * `historical_summaries` - Reserved for future post-Capella support
+pub fn verify_inclusion_proofs_detailed(
+    pre_merge_accumulator_file: Option<PreMergeAccumulator>,
+    header_proofs: Vec<HeaderWithProof>,
+    historical_summaries: Option<HistoricalSummaries>,
+) -> Vec<(BlockNumber, Result<(), EraValidationError>)> {
+    let header_validator = header_validator(pre_merge_accumulator_file, historical_summaries);
+
+    header_proofs
+        .into_iter()
+        .map(|provable_header| {
+            let block_number = BlockNumber(provable_header.header.number);
+            (
+                block_number,
+                verify_inclusion_proof(&header_validator, provable_header),
+            )
+        })
+        .collect()
+}
+
+fn header_validator(
+    pre_merge_accumulator_file: Option<PreMergeAccumulator>,
+    historical_summaries: Option<HistoricalSummaries>,
+) -> HeaderValidator {
+    let mut builder = HeaderValidatorBuilder::default();
+    if let Some(pre_merge_acc) = pre_merge_accumulator_file {
+        builder = builder.pre_merge_acc(pre_merge_acc);
+    }
+    if let Some(historical_summaries) = historical_summaries {
+        builder = builder.historical_summaries(historical_summaries);
+    }
+    builder.build()
+}
+
+/// Builds a [`HeaderValidator`] from custom accumulators
+///
+/// Accumulators which are not set default to the embedded mainnet accumulators, and historical
+/// summaries default to none, which disables post-Capella validation. This allows validating
+/// against non-default historical roots, such as in tests or on other networks.
+#[derive(Clone, Default)]
+pub struct HeaderValidatorBuilder {
+    pre_merge_acc: Option<PreMergeAccumulator>,
+    historical_roots_acc: Option<HistoricalRootsAccumulator>,
+    historical_summaries: Option<HistoricalSummaries>,
+}
+
+impl HeaderValidatorBuilder {
+    /// Sets the pre-merge accumulator used to validate pre-merge headers
+    pub fn pre_merge_acc(mut self, pre_merge_acc: PreMergeAccumulator) -> Self {
+        self.pre_merge_acc = Some(pre_merge_acc);
+        self
+    }
+
+    /// Sets the historical roots accumulator used to validate post-merge, pre-Capella headers
+    pub fn historical_roots_acc(
+        mut self,
+        historical_roots_acc: HistoricalRootsAccumulator,
+    ) -> Self {
+        self.historical_roots_acc = Some(historical_roots_acc);
+        self
+    }
+
+    /// Sets the historical summaries used to validate post-Capella headers
+    pub fn historical_summaries(mut self, historical_summaries: HistoricalSummaries) -> Self {
+        self.historical_summaries = Some(historical_summaries);
+        self
+    }
+
+    /// Returns the configured [`HeaderValidator`]
+    pub fn build(self) -> HeaderValidator {
+        HeaderValidator {
+            pre_merge_acc: self.pre_merge_acc.unwrap_or_default(),
+            historical_roots_acc: self.historical_roots_acc.unwrap_or_default(),
+            historical_summaries: self.historical_summaries,
+        }
+    }
+}
+
 /// A header with an inclusion proof attached
+#[derive(Clone)]
 pub struct HeaderWithProof {
-    header: Header,
-    proof: InclusionProof,
+    pub(crate) header: Header,
+    pub(crate) proof: InclusionProof,
 }
 
 /// Verifies if a proof is contained in the header validator
@@ -380,3 +495,215 @@ pub fn verify_inclusion_proof(
         .validate_header_with_proof(&hwp)
         .map_err(|_| EraValidationError::ProofValidationFailure)
 }
+
+/// Verifies provable headers one at a time against a single cached [`HeaderValidator`]
+///
+/// Unlike [`verify_inclusion_proofs`], headers do not need to be collected up front, so
+/// arbitrarily large ranges can be verified with bounded memory. Each call to
+/// [`StreamingVerifier::verify_next`] updates a running tally, available through
+/// [`StreamingVerifier::report`].
+pub struct StreamingVerifier {
+    header_validator: HeaderValidator,
+    report: VerificationReport,
+}
+
+/// Tally of the headers seen by a [`StreamingVerifier`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerificationReport {
+    /// Number of headers whose inclusion proof verified
+    pub verified: u64,
+    /// Number of headers whose inclusion proof failed to verify
+    pub failed: u64,
+    /// Block number of the first header that failed to verify, if any
+    pub first_failure: Option<BlockNumber>,
+}
+
+impl VerificationReport {
+    /// Total number of headers seen
+    pub fn total(&self) -> u64 {
+        self.verified + self.failed
+    }
+
+    /// Returns `true` if every header seen so far verified
+    pub fn all_verified(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+impl StreamingVerifier {
+    /// Creates a streaming verifier backed by the given header validator
+    pub fn new(header_validator: HeaderValidator) -> Self {
+        Self {
+            header_validator,
+            report: VerificationReport::default(),
+        }
+    }
+
+    /// Verifies the next provable header and records the outcome in the report
+    pub fn verify_next(
+        &mut self,
+        provable_header: HeaderWithProof,
+    ) -> Result<(), EraValidationError> {
+        let block_number = BlockNumber(provable_header.header.number);
+        let result = verify_inclusion_proof(&self.header_validator, provable_header);
+
+        match result {
+            Ok(()) => self.report.verified += 1,
+            Err(_) => {
+                self.report.failed += 1;
+                self.report.first_failure.get_or_insert(block_number);
+            }
+        }
+
+        result
+    }
+
+    /// Returns the tally of headers verified so far
+    pub fn report(&self) -> VerificationReport {
+        self.report
+    }
+}
+
+impl Default for StreamingVerifier {
+    fn default() -> Self {
+        Self::new(HeaderValidator::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use super::*;
+    use crate::ethereum::test_utils::{epoch_records, pre_merge_accumulator};
+
+    #[test]
+    fn inclusion_proof_bytes_round_trip() {
+        let records = epoch_records(0);
+        let header = records[42].full_header.clone().unwrap();
+        let epoch = Epoch::try_from(records).unwrap();
+        let header_validator = HeaderValidatorBuilder::default()
+            .pre_merge_acc(pre_merge_accumulator(&[&epoch]))
+            .build();
+
+        let proof = generate_inclusion_proof(header.clone(), epoch).unwrap();
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), INCLUSION_PROOF_LENGTH);
+
+        let decoded = InclusionProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+
+        // a deserialized proof still attaches to its header and verifies
+        let provable_header = decoded.with_header(header.clone()).unwrap();
+        verify_inclusion_proof(&header_validator, provable_header).unwrap();
+
+        let other_header = Header {
+            number: 43,
+            ..header
+        };
+        assert!(matches!(
+            InclusionProof::from_bytes(&bytes)
+                .unwrap()
+                .with_header(other_header),
+            Err(EraValidationError::HeaderMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn inclusion_proof_rejects_wrong_length() {
+        let bytes = [0u8; INCLUSION_PROOF_LENGTH + 1];
+
+        assert!(matches!(
+            InclusionProof::from_bytes(&bytes[..INCLUSION_PROOF_LENGTH - 1]),
+            Err(EraValidationError::InvalidInclusionProofLength(length))
+                if length == INCLUSION_PROOF_LENGTH - 1
+        ));
+        assert!(matches!(
+            InclusionProof::from_bytes(&bytes),
+            Err(EraValidationError::InvalidInclusionProofLength(length))
+                if length == INCLUSION_PROOF_LENGTH + 1
+        ));
+    }
+
+    #[test]
+    fn builds_validator_from_custom_accumulators() {
+        let records = epoch_records(0);
+        let header = records[7].full_header.clone().unwrap();
+        let epoch = Epoch::try_from(records).unwrap();
+        let pre_merge_acc = pre_merge_accumulator(&[&epoch]);
+        let historical_roots_acc = HistoricalRootsAccumulator {
+            historical_roots: vec![B256::repeat_byte(0x01)].into(),
+        };
+
+        let header_validator = HeaderValidatorBuilder::default()
+            .pre_merge_acc(pre_merge_acc.clone())
+            .historical_roots_acc(historical_roots_acc.clone())
+            .build();
+        assert_eq!(
+            header_validator.pre_merge_acc.historical_epochs,
+            pre_merge_acc.historical_epochs
+        );
+        assert_eq!(
+            header_validator.historical_roots_acc.historical_roots,
+            historical_roots_acc.historical_roots
+        );
+        assert!(header_validator.historical_summaries.is_none());
+
+        // the synthetic epoch only verifies against the accumulator committing to it
+        let provable_header = generate_inclusion_proof(header.clone(), epoch)
+            .unwrap()
+            .with_header(header)
+            .unwrap();
+        verify_inclusion_proof(&header_validator, provable_header.clone()).unwrap();
+        assert!(matches!(
+            verify_inclusion_proof(&HeaderValidatorBuilder::default().build(), provable_header),
+            Err(EraValidationError::ProofValidationFailure)
+        ));
+    }
+
+    #[test]
+    fn streaming_verifier_tallies_outcomes() {
+        let records = epoch_records(0);
+        let headers: Vec<Header> = [10, 20, 30, 40]
+            .iter()
+            .map(|&number| records[number].full_header.clone().unwrap())
+            .collect();
+        let epoch = Epoch::try_from(records).unwrap();
+        let mut verifier = StreamingVerifier::new(
+            HeaderValidatorBuilder::default()
+                .pre_merge_acc(pre_merge_accumulator(&[&epoch]))
+                .build(),
+        );
+
+        let report = verifier.report();
+        assert_eq!(report.total(), 0);
+        assert!(report.all_verified());
+
+        for header in headers {
+            let number = header.number;
+            let mut provable_header = generate_inclusion_proof(header.clone(), epoch.clone())
+                .unwrap()
+                .with_header(header)
+                .unwrap();
+            // tamper with the proofs of blocks 20 and 40
+            if number % 20 == 0 {
+                provable_header.proof.proof[0] = B256::repeat_byte(0xff);
+                assert!(verifier.verify_next(provable_header).is_err());
+            } else {
+                verifier.verify_next(provable_header).unwrap();
+            }
+        }
+
+        let report = verifier.report();
+        assert_eq!(
+            report,
+            VerificationReport {
+                verified: 2,
+                failed: 2,
+                first_failure: Some(BlockNumber(20)),
+            }
+        );
+        assert_eq!(report.total(), 4);
+        assert!(!report.all_verified());
+    }
+}
@@ -0,0 +1,35 @@
+// SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! synthetic epochs shared by the ethereum tests
+
+use alloy_consensus::Header;
+use alloy_primitives::U256;
+use validation::PreMergeAccumulator;
+
+use crate::ethereum::types::{block_range_of_epoch, Epoch, ExtHeaderRecord};
+
+/// the records of a synthetic epoch, where every block has a difficulty of 1
+pub(crate) fn epoch_records(epoch: u64) -> Vec<ExtHeaderRecord> {
+    block_range_of_epoch(epoch)
+        .map(|number| {
+            let header = Header {
+                number,
+                difficulty: U256::from(1),
+                ..Default::default()
+            };
+            ExtHeaderRecord::new(header, U256::from(number + 1))
+        })
+        .collect()
+}
+
+/// a pre-merge accumulator committing to the given epochs, starting at epoch 0
+pub(crate) fn pre_merge_accumulator(epochs: &[&Epoch]) -> PreMergeAccumulator {
+    PreMergeAccumulator {
+        historical_epochs: epochs
+            .iter()
+            .map(|epoch| epoch.accumulator_root())
+            .collect::<Vec<_>>()
+            .into(),
+    }
+}
@@ -1,23 +1,20 @@
 // SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::array::IntoIter;
+use std::{array::IntoIter, collections::BTreeMap, ops::RangeInclusive};
 
 use alloy_consensus::Header;
 use alloy_primitives::{Uint, B256};
 use ethportal_api::types::execution::accumulator::{EpochAccumulator, HeaderRecord};
 #[cfg(feature = "firehose")]
 use firehose_protos::{BlockHeader, EthBlock as Block, ProtosError};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use tree_hash::TreeHash;
 
 use crate::error::EraValidationError;
 use crate::types::{BlockNumber, EpochNumber};
 
-/// the maximum number of slots per epoch in ethereum.
-///
-/// in the context of proof of stake (pos) consensus, an epoch is a collection of slots
-/// during which validators propose and attest to blocks. the maximum size of an epoch
-/// defines the number of slots that can be included in one epoch.
-pub const MAX_EPOCH_SIZE: usize = 8192;
+pub use crate::types::MAX_EPOCH_SIZE;
 
 /// the final epoch number before the ethereum network underwent "the merge."
 ///
@@ -31,18 +28,113 @@ pub const FINAL_EPOCH: usize = 1896;
 /// from proof of work (pow) to proof of stake (pos).
 pub const MERGE_BLOCK: u64 = 15537394;
 
+/// returns the epoch containing `block_number`
+///
+/// epochs are runs of [`MAX_EPOCH_SIZE`] (8192) consecutive blocks starting at block 0, so block
+/// 8191 is the last block of epoch 0 and block 8192 the first block of epoch 1. [`MERGE_BLOCK`]
+/// falls in [`FINAL_EPOCH`], the last epoch of the pre-merge accumulator.
+pub fn epoch_of_block(block_number: u64) -> u64 {
+    block_number / MAX_EPOCH_SIZE as u64
+}
+
+/// returns the block numbers of `epoch`, the inverse of [`epoch_of_block`]
+///
+/// the range always spans [`MAX_EPOCH_SIZE`] blocks. for [`FINAL_EPOCH`] this includes blocks
+/// from [`MERGE_BLOCK`] onwards, although only the blocks before it are pre-merge.
+pub fn block_range_of_epoch(epoch: u64) -> RangeInclusive<u64> {
+    let start = epoch * MAX_EPOCH_SIZE as u64;
+    start..=start + (MAX_EPOCH_SIZE as u64 - 1)
+}
+
 /// epoch containing 8192 blocks
 ///
 /// an epoch must respect the order of blocks, i.e., block numbers for epoch
 /// 0 must start from block 0 to block 8191.
 ///
-/// all blocks must be at the same epoch
+/// all blocks must be at the same epoch, and each block's total difficulty must equal the
+/// previous block's total difficulty plus its own difficulty. a record built without its full
+/// header, e.g. with [`ExtHeaderRecord::new_minimal`], does not carry its difficulty, so for it
+/// the total difficulty is only checked not to decrease.
+///
+/// an epoch serializes as its number and its header records, so a reconstructed epoch can be
+/// saved and reloaded without streaming its blocks again. each serialized record carries its
+/// block number, and deserializing runs the records through the same checks as
+/// [`Epoch::try_from`], so a corrupted file is rejected rather than loaded as an epoch.
 #[derive(Clone)]
 pub struct Epoch {
     number: EpochNumber,
     data: Box<[HeaderRecord; MAX_EPOCH_SIZE]>,
 }
 
+/// serialized form of an [`Epoch`]
+#[derive(Serialize, Deserialize)]
+struct EpochData {
+    number: u64,
+    header_records: Vec<HeaderRecordData>,
+}
+
+/// serialized form of a [`HeaderRecord`], together with its block number
+#[derive(Serialize, Deserialize)]
+struct HeaderRecordData {
+    block_number: BlockNumber,
+    block_hash: B256,
+    total_difficulty: Uint<256, 4>,
+}
+
+impl Serialize for Epoch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EpochData {
+            number: self.number.0,
+            header_records: block_range_of_epoch(self.number.0)
+                .zip(self.iter())
+                .map(|(block_number, record)| HeaderRecordData {
+                    block_number: BlockNumber(block_number),
+                    block_hash: record.block_hash,
+                    total_difficulty: record.total_difficulty,
+                })
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Epoch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let EpochData {
+            number,
+            header_records,
+        } = EpochData::deserialize(deserializer)?;
+
+        // `Epoch::try_from` keeps the first 8192 records, so extra records are rejected here
+        if header_records.len() != MAX_EPOCH_SIZE {
+            return Err(de::Error::custom(EraValidationError::InvalidEpochLength(
+                header_records.len() as u64,
+            )));
+        }
+
+        let records = header_records
+            .into_iter()
+            .map(|record| {
+                ExtHeaderRecord::new_minimal(
+                    record.block_hash,
+                    record.total_difficulty,
+                    record.block_number,
+                )
+            })
+            .collect::<Vec<_>>();
+        let epoch = Epoch::try_from(records).map_err(de::Error::custom)?;
+
+        if epoch.number != EpochNumber(number) {
+            return Err(de::Error::custom(EraValidationError::EpochNumberMismatch {
+                expected: EpochNumber(number),
+                actual: epoch.number,
+            }));
+        }
+
+        Ok(epoch)
+    }
+}
+
 impl TryFrom<Vec<ExtHeaderRecord>> for Epoch {
     type Error = EraValidationError;
 
@@ -70,6 +162,16 @@ impl TryFrom<Vec<ExtHeaderRecord>> for Epoch {
             });
         }
 
+        // total difficulty must accumulate the difficulty of every block
+        if let Some(w) = data
+            .windows(2)
+            .find(|w| !total_difficulty_is_consistent(&w[0], &w[1]))
+        {
+            return Err(EraValidationError::TotalDifficultyInconsistent {
+                block_number: w[1].block_number,
+            });
+        }
+
         // check if all blocks are in the same era
         let mut epochs_found: Vec<EpochNumber> =
             data.iter().map(|block| block.block_number.into()).collect();
@@ -89,6 +191,20 @@ impl TryFrom<Vec<ExtHeaderRecord>> for Epoch {
     }
 }
 
+/// checks that `record` carries the cumulative total difficulty following `previous`
+///
+/// records without a full header only carry their total difficulty, so for those the total can
+/// only be checked to be non-decreasing.
+fn total_difficulty_is_consistent(previous: &ExtHeaderRecord, record: &ExtHeaderRecord) -> bool {
+    match &record.full_header {
+        Some(header) => {
+            previous.total_difficulty.checked_add(header.difficulty)
+                == Some(record.total_difficulty)
+        }
+        None => record.total_difficulty >= previous.total_difficulty,
+    }
+}
+
 impl From<Epoch> for EpochAccumulator {
     fn from(value: Epoch) -> Self {
         let vec: Vec<HeaderRecord> = value.data.to_vec();
@@ -97,15 +213,48 @@ impl From<Epoch> for EpochAccumulator {
 }
 
 impl Epoch {
+    /// splits headers spanning one or more epochs into [`Epoch`]s, in ascending order
+    ///
+    /// headers are grouped by `block_number / MAX_EPOCH_SIZE` and each group must be a complete
+    /// epoch of 8192 contiguous blocks, otherwise the error of the first incomplete epoch is
+    /// returned.
+    pub fn split_into_epochs(
+        headers: Vec<ExtHeaderRecord>,
+    ) -> Result<Vec<Epoch>, EraValidationError> {
+        let mut groups: BTreeMap<EpochNumber, Vec<ExtHeaderRecord>> = BTreeMap::new();
+        for header in headers {
+            groups
+                .entry(header.block_number.into())
+                .or_default()
+                .push(header);
+        }
+
+        groups.into_values().map(Epoch::try_from).collect()
+    }
+
     /// get the epoch number
     pub fn number(&self) -> EpochNumber {
         self.number
     }
 
+    /// computes the tree hash root of the epoch's [`EpochAccumulator`]
+    ///
+    /// for pre-merge epochs this is the value committed to in the header accumulator, so it can
+    /// be compared against the canonical portal network epoch roots, e.g.
+    /// `PreMergeAccumulator::default().historical_epochs[epoch.number()]`.
+    pub fn accumulator_root(&self) -> B256 {
+        EpochAccumulator::from(self.data.to_vec()).tree_hash_root()
+    }
+
     /// get an iterator over the epoch data
     pub fn iter(&self) -> std::slice::Iter<'_, HeaderRecord> {
         self.data.iter()
     }
+
+    /// get the header records of the epoch, in block order
+    pub fn header_records(&self) -> &[HeaderRecord] {
+        self.data.as_slice()
+    }
 }
 
 impl IntoIterator for Epoch {
@@ -201,11 +350,27 @@ impl From<&ExtHeaderRecord> for HeaderRecord {
 
 /// decodes a [`ExtHeaderRecord`] from a [`Block`]. a [`BlockHeader`] must be present in the block,
 /// otherwise validating headers won't be possible
+///
+/// see [`ExtHeaderRecord::from_eth_block`].
 #[cfg(feature = "firehose")]
 impl TryFrom<&Block> for ExtHeaderRecord {
     type Error = EraValidationError;
 
     fn try_from(block: &Block) -> Result<Self, Self::Error> {
+        ExtHeaderRecord::from_eth_block(block)
+    }
+}
+
+#[cfg(feature = "firehose")]
+impl ExtHeaderRecord {
+    /// builds an [`ExtHeaderRecord`] from a firehose [`Block`], always filling `full_header`
+    ///
+    /// the full header is converted from the block's [`BlockHeader`], and the total difficulty is
+    /// the cumulative total difficulty recorded in that header, not the block's own difficulty.
+    ///
+    /// fails with [`EraValidationError::HeaderDecode`] if the block has no header, if the header
+    /// has no total difficulty, or if any header field cannot be converted.
+    pub fn from_eth_block(block: &Block) -> Result<Self, EraValidationError> {
         let header: &BlockHeader =
             block
                 .header
@@ -219,7 +384,9 @@ impl TryFrom<&Block> for ExtHeaderRecord {
                 .total_difficulty
                 .as_ref()
                 .ok_or(EraValidationError::HeaderDecode(
-                    ProtosError::BlockConversionError,
+                    ProtosError::FieldConversion {
+                        field: "total_difficulty",
+                    },
                 ))?;
 
         Ok(ExtHeaderRecord {
@@ -230,3 +397,244 @@ impl TryFrom<&Block> for ExtHeaderRecord {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::U256;
+
+    use super::*;
+    use crate::ethereum::test_utils::epoch_records;
+
+    #[test]
+    fn maps_blocks_to_epochs() {
+        for (block, epoch) in [
+            (0, 0),
+            (8191, 0),
+            (8192, 1),
+            (MERGE_BLOCK, FINAL_EPOCH as u64),
+        ] {
+            assert_eq!(epoch_of_block(block), epoch);
+            assert_eq!(EpochNumber::from(BlockNumber(block)), EpochNumber(epoch));
+            assert!(block_range_of_epoch(epoch).contains(&block));
+        }
+    }
+
+    #[test]
+    fn block_ranges_cover_whole_epochs() {
+        assert_eq!(block_range_of_epoch(0), 0..=8191);
+        assert_eq!(block_range_of_epoch(1), 8192..=16383);
+
+        let final_epoch = block_range_of_epoch(FINAL_EPOCH as u64);
+        assert!(final_epoch.contains(&(MERGE_BLOCK - 1)));
+        assert_eq!(
+            epoch_of_block(*final_epoch.end() + 1),
+            FINAL_EPOCH as u64 + 1
+        );
+    }
+
+    #[test]
+    fn accepts_accumulated_total_difficulty() {
+        let epoch = Epoch::try_from(epoch_records(0)).unwrap();
+        assert_eq!(epoch.number(), EpochNumber(0));
+    }
+
+    #[test]
+    fn rejects_per_block_difficulty_as_total_difficulty() {
+        let mut records = epoch_records(0);
+        records[100].total_difficulty = U256::from(1);
+
+        assert!(matches!(
+            Epoch::try_from(records),
+            Err(EraValidationError::TotalDifficultyInconsistent {
+                block_number: BlockNumber(100)
+            })
+        ));
+    }
+
+    #[test]
+    fn checks_minimal_records_are_non_decreasing() {
+        let minimal = |records: Vec<ExtHeaderRecord>| -> Vec<ExtHeaderRecord> {
+            records
+                .into_iter()
+                .map(|record| {
+                    ExtHeaderRecord::new_minimal(
+                        record.block_hash,
+                        record.total_difficulty,
+                        record.block_number,
+                    )
+                })
+                .collect()
+        };
+
+        assert!(Epoch::try_from(minimal(epoch_records(0))).is_ok());
+
+        let mut records = minimal(epoch_records(0));
+        records[100].total_difficulty = U256::ZERO;
+        assert!(matches!(
+            Epoch::try_from(records),
+            Err(EraValidationError::TotalDifficultyInconsistent {
+                block_number: BlockNumber(100)
+            })
+        ));
+    }
+
+    #[test]
+    fn splits_records_into_epochs() {
+        let mut records = epoch_records(1);
+        records.extend(epoch_records(0));
+
+        let epochs = Epoch::split_into_epochs(records).unwrap();
+        assert_eq!(epochs.len(), 2);
+        for (epoch, number) in epochs.iter().zip(0..) {
+            assert_eq!(epoch.number(), EpochNumber(number));
+            assert_eq!(
+                epoch.accumulator_root(),
+                Epoch::try_from(epoch_records(number))
+                    .unwrap()
+                    .accumulator_root()
+            );
+        }
+    }
+
+    #[test]
+    fn split_rejects_short_epoch() {
+        let mut records = epoch_records(0);
+        records.extend(epoch_records(1));
+        records.pop();
+
+        assert!(matches!(
+            Epoch::split_into_epochs(records),
+            Err(EraValidationError::InvalidEpochLength(8191))
+        ));
+    }
+
+    #[test]
+    fn split_rejects_gap() {
+        let mut records = epoch_records(0);
+        records.extend(epoch_records(1));
+        records.remove(MAX_EPOCH_SIZE + 100);
+
+        assert!(matches!(
+            Epoch::split_into_epochs(records),
+            Err(EraValidationError::MissingBlock { blocks, epoch })
+                if blocks == vec![BlockNumber(8292)] && epoch == EpochNumber(1)
+        ));
+    }
+
+    #[test]
+    fn accumulator_root_is_the_ssz_list_root_of_its_records() {
+        let epoch = Epoch::try_from(epoch_records(0)).unwrap();
+
+        // hash each (block_hash, total_difficulty) container, then merkleize the full list
+        let leaves: Vec<u8> = epoch
+            .iter()
+            .flat_map(|record| {
+                let mut container = record.block_hash.to_vec();
+                container.extend_from_slice(&record.total_difficulty.to_le_bytes::<32>());
+                tree_hash::merkle_root(&container, 2).0
+            })
+            .collect();
+        let expected = tree_hash::mix_in_length(
+            &tree_hash::merkle_root(&leaves, MAX_EPOCH_SIZE),
+            MAX_EPOCH_SIZE,
+        );
+
+        assert_eq!(epoch.accumulator_root(), expected);
+    }
+
+    fn serialized_epoch(epoch: u64) -> serde_json::Value {
+        serde_json::to_value(Epoch::try_from(epoch_records(epoch)).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn epoch_serde_round_trip() {
+        let epoch = Epoch::try_from(epoch_records(1)).unwrap();
+
+        let json = serde_json::to_string(&epoch).unwrap();
+        let deserialized: Epoch = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.number(), epoch.number());
+        assert_eq!(deserialized.accumulator_root(), epoch.accumulator_root());
+    }
+
+    #[test]
+    fn epoch_deserialization_rejects_wrong_number() {
+        let mut value = serialized_epoch(1);
+        value["number"] = 2.into();
+
+        let error = serde_json::from_value::<Epoch>(value).err().unwrap();
+        assert!(error.to_string().contains("does not match"));
+    }
+
+    #[test]
+    fn epoch_deserialization_rejects_gaps() {
+        let mut value = serialized_epoch(1);
+        value["header_records"][100]["block_number"] = 8191.into();
+
+        let error = serde_json::from_value::<Epoch>(value).err().unwrap();
+        assert!(error.to_string().contains("missing"));
+    }
+
+    #[test]
+    fn epoch_deserialization_rejects_extra_records() {
+        let mut value = serialized_epoch(1);
+        let last = value["header_records"][MAX_EPOCH_SIZE - 1].clone();
+        value["header_records"].as_array_mut().unwrap().push(last);
+
+        let error = serde_json::from_value::<Epoch>(value).err().unwrap();
+        assert!(error.to_string().contains("8193"));
+    }
+
+    #[cfg(feature = "firehose")]
+    fn mainnet_blocks() -> Vec<Block> {
+        let file = std::fs::File::open("../decoder/tests/0000000000.dbin").unwrap();
+        flat_files_decoder::read_eth_blocks_from_reader(file, flat_files_decoder::Compression::None)
+            .unwrap()
+    }
+
+    #[cfg(feature = "firehose")]
+    #[test]
+    fn builds_records_from_firehose_blocks() {
+        let blocks = mainnet_blocks();
+        let records: Vec<ExtHeaderRecord> = blocks
+            .iter()
+            .map(ExtHeaderRecord::from_eth_block)
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        for (record, block) in records.iter().zip(&blocks) {
+            let full_header = record.full_header.as_ref().unwrap();
+            assert_eq!(record.block_number, BlockNumber(block.number));
+            assert_eq!(full_header.hash_slow(), record.block_hash);
+        }
+
+        // the cumulative total difficulty of real blocks passes the epoch check
+        assert!(records
+            .windows(2)
+            .all(|w| total_difficulty_is_consistent(&w[0], &w[1])));
+    }
+
+    #[cfg(feature = "firehose")]
+    #[test]
+    fn rejects_firehose_blocks_without_total_difficulty() {
+        let mut block = mainnet_blocks().swap_remove(1);
+        block.header.as_mut().unwrap().total_difficulty = None;
+
+        assert!(matches!(
+            ExtHeaderRecord::from_eth_block(&block),
+            Err(EraValidationError::HeaderDecode(
+                ProtosError::FieldConversion {
+                    field: "total_difficulty"
+                }
+            ))
+        ));
+
+        block.header = None;
+        assert!(matches!(
+            ExtHeaderRecord::from_eth_block(&block),
+            Err(EraValidationError::HeaderDecode(
+                ProtosError::BlockHeaderMissing
+            ))
+        ));
+    }
+}
@@ -96,12 +96,16 @@ pub use types::{BlockNumber, EpochNumber, EraNumber, SlotNumber};
 // re-export ethereum types and validators
 pub use ethereum::{
     generate_inclusion_proof, generate_inclusion_proofs, verify_inclusion_proof,
-    verify_inclusion_proofs, Epoch, EthereumPreMergeValidator, ExtHeaderRecord, HeaderWithProof,
-    InclusionProof,
+    verify_inclusion_proofs, verify_inclusion_proofs_detailed, Epoch, EthereumPreMergeValidator,
+    ExtHeaderRecord, HeaderWithProof, InclusionProof, ProofBundle, StreamingVerifier,
+    VerificationReport,
 };
 
 #[cfg(feature = "beacon")]
-pub use ethereum::{EthereumPostCapellaValidator, EthereumPostMergeValidator};
+pub use ethereum::{
+    generate_post_merge_inclusion_proof, verify_post_merge_inclusion_proof,
+    EthereumPostCapellaValidator, EthereumPostMergeValidator, HistoricalRootsContext,
+};
 
 // re-export solana types and validators
 #[cfg(feature = "solana")]
@@ -1,12 +1,22 @@
 // SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{error::SolanaValidatorError, traits::EraValidationContext, types::EpochNumber};
+use crate::{
+    error::SolanaValidatorError,
+    traits::EraValidationContext,
+    types::{EpochNumber, SlotNumber},
+};
 use alloy_primitives::FixedBytes;
+#[cfg(feature = "firehose")]
+use firehose_protos::SolBlock;
 use merkle_proof::MerkleTree;
 use primitive_types::H256;
 
+#[cfg(not(test))]
 const SOLANA_EPOCH_LENGTH: usize = 432_000;
+/// a reduced era size, so tests can build full eras of slots
+#[cfg(test)]
+const SOLANA_EPOCH_LENGTH: usize = 8;
 const SOLANA_HISTORICAL_TREE_DEPTH: usize = 19;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -38,6 +48,115 @@ impl SolanaValidator {
     ) -> Result<(), SolanaValidatorError> {
         self.historical_roots.validate_era(input)
     }
+
+    /// Verifies the block hashes of a full era of slots against an expected era root.
+    ///
+    /// `start_slot` must be the first slot of an era, i.e. a multiple of 432,000, and
+    /// `slot_hashes` must hold one hash per slot of the era, with zero hashes for skipped slots.
+    /// The Merkle root of the hashes is compared to `expected_root`, so slot ranges can be
+    /// verified against a root obtained out of band rather than the validator's historical roots.
+    pub fn verify_slot_range(
+        &self,
+        start_slot: u64,
+        slot_hashes: &[H256],
+        expected_root: H256,
+    ) -> Result<(), SolanaValidatorError> {
+        let epoch_length = SOLANA_EPOCH_LENGTH as u64;
+        if start_slot % epoch_length != 0 {
+            return Err(SolanaValidatorError::InvalidEraStart(SlotNumber(
+                start_slot,
+            )));
+        }
+        if slot_hashes.len() != SOLANA_EPOCH_LENGTH {
+            return Err(SolanaValidatorError::MismatchedBlockCount);
+        }
+
+        let root = era_root(slot_hashes);
+        if root != expected_root {
+            return Err(SolanaValidatorError::InvalidHistoricalRoot {
+                era: EpochNumber(start_slot / epoch_length),
+                expected: expected_root,
+                actual: root,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Validates the decoded blocks of an era.
+    ///
+    /// Two checks are performed:
+    /// - the blocks form a continuous hash chain, i.e. each block's `parent_slot` and
+    ///   `previous_blockhash` point at the block before it,
+    /// - the Merkle root of the era's block hashes matches the historical root for the era.
+    ///
+    /// `blocks` must be ordered by slot and belong to `era`. Each block hash is committed at
+    /// its slot offset within the era; skipped slots are committed as zero hashes.
+    #[cfg(feature = "firehose")]
+    pub fn validate_era_blocks(
+        &self,
+        era: u64,
+        blocks: &[SolBlock],
+    ) -> Result<(), SolanaValidatorError> {
+        let era_number = EpochNumber(era);
+        let epoch_length = SOLANA_EPOCH_LENGTH as u64;
+        let first_slot = era
+            .checked_mul(epoch_length)
+            .ok_or_else(|| self.historical_roots.epoch_out_of_bounds(era_number))?;
+
+        let mut block_hashes = vec![H256::zero(); SOLANA_EPOCH_LENGTH];
+        let mut previous: Option<&SolBlock> = None;
+
+        for block in blocks {
+            if block.slot / epoch_length != era {
+                return Err(SolanaValidatorError::SlotOutsideEra {
+                    slot: SlotNumber(block.slot),
+                    era: era_number,
+                });
+            }
+
+            if let Some(parent) = previous {
+                if block.parent_slot != parent.slot || block.previous_blockhash != parent.blockhash
+                {
+                    return Err(SolanaValidatorError::BrokenHashChain {
+                        slot: SlotNumber(block.slot),
+                        expected_parent: parent.blockhash.clone(),
+                        actual_parent: block.previous_blockhash.clone(),
+                    });
+                }
+            }
+
+            block_hashes[(block.slot - first_slot) as usize] = decode_block_hash(&block.blockhash)?;
+            previous = Some(block);
+        }
+
+        self.validate_era((era_number, block_hashes))
+    }
+}
+
+/// Decodes a base58 encoded Solana block hash.
+#[cfg(feature = "firehose")]
+fn decode_block_hash(block_hash: &str) -> Result<H256, SolanaValidatorError> {
+    let bytes = bs58::decode(block_hash)
+        .into_vec()
+        .map_err(|_| SolanaValidatorError::InvalidBlockHash(block_hash.to_string()))?;
+
+    if bytes.len() != 32 {
+        return Err(SolanaValidatorError::InvalidBlockHash(
+            block_hash.to_string(),
+        ));
+    }
+
+    Ok(H256::from_slice(&bytes))
+}
+
+impl SolanaHistoricalRoots {
+    fn epoch_out_of_bounds(&self, epoch: EpochNumber) -> SolanaValidatorError {
+        SolanaValidatorError::EpochOutOfBounds {
+            epoch,
+            max_epoch: EpochNumber::from(self.0.len().saturating_sub(1) as u64),
+        }
+    }
 }
 
 impl EraValidationContext for SolanaHistoricalRoots {
@@ -51,33 +170,193 @@ impl EraValidationContext for SolanaHistoricalRoots {
             return Err(SolanaValidatorError::MismatchedBlockCount);
         }
 
-        let block_roots_fixedbytes = block_roots
-            .iter()
-            .map(|h| FixedBytes::<32>::from(h.0))
-            .collect::<Vec<_>>();
-
-        let root = MerkleTree::create(
-            block_roots_fixedbytes.as_slice(),
-            SOLANA_HISTORICAL_TREE_DEPTH,
-        )
-        .hash();
+        let root = era_root(&block_roots);
 
         let era_idx = usize::from(era_number);
         if era_idx >= self.0.len() {
-            return Err(SolanaValidatorError::EpochOutOfBounds {
-                epoch: era_number,
-                max_epoch: EpochNumber::from(self.0.len().saturating_sub(1) as u64),
-            });
+            return Err(self.epoch_out_of_bounds(era_number));
         }
 
         // Check that root matches the expected historical root
-        if H256::from(root.0) != self.0[era_idx] {
+        if root != self.0[era_idx] {
             return Err(SolanaValidatorError::InvalidHistoricalRoot {
                 era: era_number,
                 expected: self.0[era_idx],
-                actual: H256::from(root.0),
+                actual: root,
             });
         }
         Ok(())
     }
 }
+
+/// Computes the Merkle root committing to the block hashes of an era.
+fn era_root(block_hashes: &[H256]) -> H256 {
+    let block_hashes_fixedbytes = block_hashes
+        .iter()
+        .map(|h| FixedBytes::<32>::from(h.0))
+        .collect::<Vec<_>>();
+
+    let root = MerkleTree::create(
+        block_hashes_fixedbytes.as_slice(),
+        SOLANA_HISTORICAL_TREE_DEPTH,
+    )
+    .hash();
+
+    H256::from(root.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot_hashes() -> Vec<H256> {
+        (0..SOLANA_EPOCH_LENGTH as u64)
+            .map(|slot| match slot {
+                // a skipped slot
+                3 => H256::zero(),
+                slot => H256::from_low_u64_be(slot + 1),
+            })
+            .collect()
+    }
+
+    fn validator() -> SolanaValidator {
+        SolanaValidator::new(SolanaHistoricalRoots(vec![]))
+    }
+
+    #[test]
+    fn verifies_slot_range_with_matching_root() {
+        let hashes = slot_hashes();
+        let start_slot = 2 * SOLANA_EPOCH_LENGTH as u64;
+
+        validator()
+            .verify_slot_range(start_slot, &hashes, era_root(&hashes))
+            .unwrap();
+    }
+
+    #[test]
+    fn rejects_slot_range_with_mismatched_root() {
+        let hashes = slot_hashes();
+        let expected_root = era_root(&hashes);
+        let mut tampered = hashes;
+        tampered[3] = H256::from_low_u64_be(4);
+        let start_slot = 2 * SOLANA_EPOCH_LENGTH as u64;
+
+        assert!(matches!(
+            validator().verify_slot_range(start_slot, &tampered, expected_root),
+            Err(SolanaValidatorError::InvalidHistoricalRoot { era, expected, .. })
+                if era == EpochNumber(2) && expected == expected_root
+        ));
+    }
+
+    #[test]
+    fn rejects_misaligned_start_slot() {
+        let hashes = slot_hashes();
+
+        assert!(matches!(
+            validator().verify_slot_range(1, &hashes, era_root(&hashes)),
+            Err(SolanaValidatorError::InvalidEraStart(SlotNumber(1)))
+        ));
+    }
+
+    #[test]
+    fn rejects_partial_slot_range() {
+        let hashes = slot_hashes();
+
+        assert!(matches!(
+            validator().verify_slot_range(0, &hashes[1..], era_root(&hashes)),
+            Err(SolanaValidatorError::MismatchedBlockCount)
+        ));
+    }
+
+    /// blocks of era 1 with a skipped slot, and the era root of their hashes
+    #[cfg(feature = "firehose")]
+    fn era_blocks() -> (Vec<SolBlock>, H256) {
+        let first_slot = SOLANA_EPOCH_LENGTH as u64;
+        let hash = |slot: u64| H256::from_low_u64_be(slot + 1);
+        let mut hashes = vec![H256::zero(); SOLANA_EPOCH_LENGTH];
+        let mut blocks: Vec<SolBlock> = Vec::new();
+
+        for slot in (first_slot..first_slot + SOLANA_EPOCH_LENGTH as u64).filter(|slot| *slot != 11)
+        {
+            hashes[(slot - first_slot) as usize] = hash(slot);
+            let (parent_slot, previous_blockhash) = match blocks.last() {
+                Some(parent) => (parent.slot, parent.blockhash.clone()),
+                None => (slot - 1, bs58::encode(hash(slot - 1)).into_string()),
+            };
+            blocks.push(SolBlock {
+                slot,
+                parent_slot,
+                blockhash: bs58::encode(hash(slot)).into_string(),
+                previous_blockhash,
+                ..Default::default()
+            });
+        }
+
+        (blocks, era_root(&hashes))
+    }
+
+    #[cfg(feature = "firehose")]
+    fn era_validator(root: H256) -> SolanaValidator {
+        SolanaValidator::new(SolanaHistoricalRoots(vec![H256::zero(), root]))
+    }
+
+    #[cfg(feature = "firehose")]
+    #[test]
+    fn validates_era_blocks_matching_root() {
+        let (blocks, root) = era_blocks();
+
+        era_validator(root).validate_era_blocks(1, &blocks).unwrap();
+
+        assert!(matches!(
+            era_validator(H256::zero()).validate_era_blocks(1, &blocks),
+            Err(SolanaValidatorError::InvalidHistoricalRoot { .. })
+        ));
+    }
+
+    #[cfg(feature = "firehose")]
+    #[test]
+    fn rejects_broken_hash_chain() {
+        let (mut blocks, root) = era_blocks();
+        blocks[4].previous_blockhash = blocks[2].blockhash.clone();
+
+        assert!(matches!(
+            era_validator(root).validate_era_blocks(1, &blocks),
+            Err(SolanaValidatorError::BrokenHashChain { slot, .. }) if slot == SlotNumber(blocks[4].slot)
+        ));
+    }
+
+    #[cfg(feature = "firehose")]
+    #[test]
+    fn rejects_blocks_outside_the_era() {
+        let (blocks, root) = era_blocks();
+
+        assert!(matches!(
+            era_validator(root).validate_era_blocks(0, &blocks),
+            Err(SolanaValidatorError::SlotOutsideEra { slot, era })
+                if slot == SlotNumber(blocks[0].slot) && era == EpochNumber(0)
+        ));
+    }
+
+    #[cfg(feature = "firehose")]
+    #[test]
+    fn rejects_malformed_block_hashes() {
+        let (mut blocks, root) = era_blocks();
+        blocks[0].blockhash = "not base58: 0OIl".to_string();
+
+        assert!(matches!(
+            era_validator(root).validate_era_blocks(1, &blocks),
+            Err(SolanaValidatorError::InvalidBlockHash(_))
+        ));
+    }
+
+    #[cfg(feature = "firehose")]
+    #[test]
+    fn rejects_eras_overflowing_the_slot_range() {
+        let (blocks, root) = era_blocks();
+
+        assert!(matches!(
+            era_validator(root).validate_era_blocks(u64::MAX, &blocks),
+            Err(SolanaValidatorError::EpochOutOfBounds { epoch, .. }) if epoch == EpochNumber(u64::MAX)
+        ));
+    }
+}
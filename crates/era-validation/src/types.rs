@@ -20,8 +20,19 @@
 
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
+/// the number of execution blocks in a pre-merge epoch.
+///
+/// the pre-merge accumulator commits to the block hashes and total difficulties of 8192
+/// consecutive blocks per epoch, so block `n` belongs to epoch `n / 8192`. post-merge eras
+/// group the same number of beacon chain slots, one historical root per 8192 slots, so the
+/// constant also sizes those eras. it is unrelated to the 32-slot proof of stake epoch.
+pub const MAX_EPOCH_SIZE: usize = 8192;
+
 /// block number in the execution layer (pre and post merge)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct BlockNumber(pub u64);
 
 /// slot number in the beacon chain (post-merge only)
@@ -140,7 +151,7 @@ impl From<EraNumber> for usize {
 // Block/Slot to Epoch/Era conversions
 impl From<BlockNumber> for EpochNumber {
     fn from(block: BlockNumber) -> EpochNumber {
-        EpochNumber(block.0 / 8192)
+        EpochNumber(block.0 / MAX_EPOCH_SIZE as u64)
     }
 }
 
@@ -54,6 +54,13 @@ pub enum ProtosError {
     #[error("Null execution payload")]
     ExecutionPayloadMissing,
 
+    /// Block header field missing or of the wrong length.
+    #[error("Missing or invalid block header field: {field}")]
+    FieldConversion {
+        /// Name of the field in the protobuf block header.
+        field: &'static str,
+    },
+
     /// Graffiti invalid when decoding block.
     #[error("GraffitiInvalid")]
     GraffitiInvalid,
@@ -78,6 +85,19 @@ pub enum ProtosError {
     #[error("Invalid log topic: {0}")]
     LogTopicInvalid(String),
 
+    /// Sender recovered from the transaction signature differs from the trace's `from` field.
+    #[error("Sender mismatch: trace from 0x{from}, recovered {recovered}")]
+    SenderMismatch {
+        /// Hex encoded `from` field of the transaction trace.
+        from: String,
+        /// Address recovered from the transaction signature.
+        recovered: alloy_primitives::Address,
+    },
+
+    /// Failed to recover the sender from the transaction signature.
+    #[error("Sender recovery error: {0}")]
+    SenderRecovery(#[source] alloy_consensus::crypto::RecoveryError),
+
     /// Missing signed Beacon block header message.
     #[error("Null SignedBeaconBlockHeader Message")]
     SignedBeaconBlockHeaderMessageMissing,
@@ -86,6 +106,10 @@ pub enum ProtosError {
     #[error("Null signer")]
     SignerMissing,
 
+    /// Solana block contents are not internally consistent.
+    #[error("Inconsistent Solana block: {0}")]
+    SolBlockInconsistent(String),
+
     /// Invalid trace signature for ECDSA component.
     #[error("Invalid trace signature {0:?} component: {1}")]
     TraceSignatureInvalid(String, String),
@@ -110,6 +134,10 @@ pub enum ProtosError {
     #[error("TxTypeConversionError: {0}")]
     TxTypeConversion(String),
 
+    /// Transaction type is not supported, such as L2-specific transaction types.
+    #[error("Unsupported transaction type: {0}")]
+    UnsupportedTxType(String),
+
     /// Missing voluntary exit.
     #[error("Null voluntary exit")]
     VoluntaryExitMissing,
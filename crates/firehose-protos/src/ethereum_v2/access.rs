@@ -3,7 +3,7 @@
 
 use crate::error::ProtosError;
 
-use super::AccessTuple;
+use super::{AccessTuple, TransactionTrace};
 
 use alloy_eip2930::AccessListItem;
 use alloy_primitives::{hex, Address, B256};
@@ -27,6 +27,21 @@ impl TryFrom<&AccessTuple> for AccessListItem {
     }
 }
 
+impl TransactionTrace {
+    /// Returns the number of addresses in the transaction's access list.
+    pub fn access_list_address_count(&self) -> usize {
+        self.access_list.len()
+    }
+
+    /// Returns the total number of storage keys across the transaction's access list.
+    pub fn access_list_storage_key_count(&self) -> usize {
+        self.access_list
+            .iter()
+            .map(|tuple| tuple.storage_keys.len())
+            .sum()
+    }
+}
+
 fn convert_to_b256(key: &Vec<u8>) -> Result<B256, ProtosError> {
     let key_bytes: [u8; 32] = key
         .as_slice()
@@ -59,6 +74,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_access_list_counts() {
+        let trace = TransactionTrace {
+            access_list: vec![
+                create_fake_access_tuple(),
+                AccessTuple {
+                    address: vec![0x22; 20],
+                    storage_keys: vec![vec![0xcc; 32]],
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(trace.access_list_address_count(), 2);
+        assert_eq!(trace.access_list_storage_key_count(), 3);
+
+        let trace = TransactionTrace::default();
+        assert_eq!(trace.access_list_address_count(), 0);
+        assert_eq!(trace.access_list_storage_key_count(), 0);
+    }
+
     #[test]
     fn test_access_tuple_with_empty_storage_keys() {
         let fake_tuple = AccessTuple {
@@ -1,17 +1,22 @@
 // SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{Block, BlockHeader, TransactionReceipt, TransactionTrace};
+use std::collections::BTreeMap;
+
+use super::{BigInt, Block, BlockHeader, TransactionReceipt, TransactionTrace};
 use alloy_consensus::{
-    proofs::{calculate_transaction_root, ordered_trie_root_with_encoder},
+    proofs::{calculate_ommers_root, calculate_transaction_root, ordered_trie_root_with_encoder},
     EthereumTxEnvelope, Header, TxEip4844,
 };
-use alloy_primitives::{Address, Bloom, FixedBytes, Uint, B256, U256};
+use alloy_primitives::{
+    keccak256, Address, Bloom, BloomInput, Bytes, FixedBytes, Uint, B256, B64, U256, U64,
+};
 use alloy_rlp::{Encodable, Header as RlpHeader};
 use firehose_rs::{FromResponse, HasNumberOrSlot, Response, SingleBlockResponse};
 use prost::Message;
-use prost_wkt_types::Any;
+use prost_wkt_types::{Any, Timestamp};
 use reth_primitives::{Log, Receipt, ReceiptWithBloom, TxType};
+use serde::{Deserialize, Serialize};
 use tracing::error;
 
 use crate::error::ProtosError;
@@ -23,20 +28,30 @@ impl TryFrom<&Block> for Header {
         let block_header = block
             .header
             .as_ref()
-            .ok_or(ProtosError::BlockConversionError)?;
-
-        let parent_hash = FixedBytes::from_slice(block_header.parent_hash.as_slice());
-        let ommers_hash = FixedBytes::from_slice(block_header.uncle_hash.as_slice());
-        let beneficiary = Address::from_slice(block_header.coinbase.as_slice());
-        let state_root = FixedBytes::from_slice(block_header.state_root.as_slice());
-        let transactions_root = FixedBytes::from_slice(block_header.transactions_root.as_slice());
-        let receipts_root = FixedBytes::from_slice(block_header.receipt_root.as_slice());
-        let logs_bloom = Bloom::from_slice(block_header.logs_bloom.as_slice());
+            .ok_or(ProtosError::BlockHeaderMissing)?;
+
+        Header::try_from(block_header)
+    }
+}
+
+impl TryFrom<&BlockHeader> for Header {
+    type Error = ProtosError;
+
+    fn try_from(block_header: &BlockHeader) -> Result<Self, Self::Error> {
+        let parent_hash = fixed_bytes(&block_header.parent_hash, "parent_hash")?;
+        let ommers_hash = fixed_bytes(&block_header.uncle_hash, "uncle_hash")?;
+        let beneficiary = Address::from(fixed_bytes::<20>(&block_header.coinbase, "coinbase")?);
+        let state_root = fixed_bytes(&block_header.state_root, "state_root")?;
+        let transactions_root = fixed_bytes(&block_header.transactions_root, "transactions_root")?;
+        let receipts_root = fixed_bytes(&block_header.receipt_root, "receipt_root")?;
+        let logs_bloom = Bloom::from(fixed_bytes::<256>(&block_header.logs_bloom, "logs_bloom")?);
         let difficulty = Uint::from_be_slice(
             block_header
                 .difficulty
                 .as_ref()
-                .ok_or(ProtosError::BlockConversionError)?
+                .ok_or(ProtosError::FieldConversion {
+                    field: "difficulty",
+                })?
                 .bytes
                 .as_slice(),
         );
@@ -46,10 +61,10 @@ impl TryFrom<&Block> for Header {
         let timestamp = block_header
             .timestamp
             .as_ref()
-            .ok_or(ProtosError::BlockConversionError)?
+            .ok_or(ProtosError::FieldConversion { field: "timestamp" })?
             .seconds as u64;
         let extra_data = block_header.extra_data.clone();
-        let mix_hash = FixedBytes::from_slice(block_header.mix_hash.as_slice());
+        let mix_hash = fixed_bytes(&block_header.mix_hash, "mix_hash")?;
         let nonce = FixedBytes::from_slice(&block_header.nonce.to_be_bytes());
         let base_fee_per_gas = block_header
             .base_fee_per_gas
@@ -57,17 +72,19 @@ impl TryFrom<&Block> for Header {
             .map(|base_fee_per_gas| U256::from_be_slice(&base_fee_per_gas.bytes).to::<u64>());
         let withdrawals_root = match block_header.withdrawals_root.is_empty() {
             true => None,
-            false => Some(FixedBytes::from_slice(
-                block_header.withdrawals_root.as_slice(),
-            )),
+            false => Some(fixed_bytes(
+                &block_header.withdrawals_root,
+                "withdrawals_root",
+            )?),
         };
         let blob_gas_used = block_header.blob_gas_used;
         let excess_blob_gas = block_header.excess_blob_gas;
         let parent_beacon_block_root = match block_header.parent_beacon_root.is_empty() {
             true => None,
-            false => Some(FixedBytes::from_slice(
-                block_header.parent_beacon_root.as_slice(),
-            )),
+            false => Some(fixed_bytes(
+                &block_header.parent_beacon_root,
+                "parent_beacon_root",
+            )?),
         };
 
         Ok(Header {
@@ -96,6 +113,177 @@ impl TryFrom<&Block> for Header {
     }
 }
 
+/// A [`BlockHeader`] in the JSON representation used by the `eth_getBlockByNumber` RPC method.
+///
+/// The derived serde implementation of [`BlockHeader`] writes byte fields as arrays of integers.
+/// This wrapper writes hashes, roots and other byte fields as `0x`-prefixed hex strings and
+/// numbers as hex quantities, with field names following the Ethereum JSON-RPC specification.
+///
+/// The Firehose-specific `tx_dependency` field has no JSON-RPC equivalent and is dropped.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexBlockHeader {
+    /// Hash of the block.
+    pub hash: B256,
+    /// Hash of the parent block.
+    pub parent_hash: B256,
+    /// Hash of the block's ommers (uncles).
+    pub sha3_uncles: B256,
+    /// Address receiving the block's priority fees and rewards.
+    pub miner: Address,
+    /// Root of the state trie.
+    pub state_root: B256,
+    /// Root of the transactions trie.
+    pub transactions_root: B256,
+    /// Root of the receipts trie.
+    pub receipts_root: B256,
+    /// Bloom filter of the logs in the block.
+    pub logs_bloom: Bloom,
+    /// Proof-of-work difficulty, zero after the merge.
+    pub difficulty: U256,
+    /// Total difficulty of the chain up to and including this block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_difficulty: Option<U256>,
+    /// Block number.
+    pub number: U64,
+    /// Gas limit of the block.
+    pub gas_limit: U64,
+    /// Gas used by the transactions in the block.
+    pub gas_used: U64,
+    /// Unix timestamp of the block, in seconds.
+    pub timestamp: U64,
+    /// Arbitrary extra data set by the block producer.
+    pub extra_data: Bytes,
+    /// Proof-of-work mix hash, the beacon chain randomness after the merge.
+    pub mix_hash: B256,
+    /// Proof-of-work nonce.
+    pub nonce: B64,
+    /// Base fee per gas, from London onwards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+    /// Root of the withdrawals trie, from Shanghai onwards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<B256>,
+    /// Blob gas used by the transactions in the block, from Cancun onwards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U64>,
+    /// Excess blob gas, from Cancun onwards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excess_blob_gas: Option<U64>,
+    /// Root of the parent beacon block, from Cancun onwards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<B256>,
+}
+
+impl TryFrom<&BlockHeader> for HexBlockHeader {
+    type Error = ProtosError;
+
+    fn try_from(block_header: &BlockHeader) -> Result<Self, Self::Error> {
+        let optional_hash = |bytes: &[u8], field| match bytes.is_empty() {
+            true => Ok(None),
+            false => fixed_bytes(bytes, field).map(Some),
+        };
+
+        Ok(Self {
+            hash: fixed_bytes(&block_header.hash, "hash")?,
+            parent_hash: fixed_bytes(&block_header.parent_hash, "parent_hash")?,
+            sha3_uncles: fixed_bytes(&block_header.uncle_hash, "uncle_hash")?,
+            miner: Address::from(fixed_bytes::<20>(&block_header.coinbase, "coinbase")?),
+            state_root: fixed_bytes(&block_header.state_root, "state_root")?,
+            transactions_root: fixed_bytes(&block_header.transactions_root, "transactions_root")?,
+            receipts_root: fixed_bytes(&block_header.receipt_root, "receipt_root")?,
+            logs_bloom: Bloom::from(fixed_bytes::<256>(&block_header.logs_bloom, "logs_bloom")?),
+            difficulty: u256_field(block_header.difficulty.as_ref(), "difficulty")?,
+            total_difficulty: block_header
+                .total_difficulty
+                .as_ref()
+                .map(|total_difficulty| u256_field(Some(total_difficulty), "total_difficulty"))
+                .transpose()?,
+            number: U64::from(block_header.number),
+            gas_limit: U64::from(block_header.gas_limit),
+            gas_used: U64::from(block_header.gas_used),
+            timestamp: U64::from(
+                block_header
+                    .timestamp
+                    .as_ref()
+                    .ok_or(ProtosError::FieldConversion { field: "timestamp" })?
+                    .seconds as u64,
+            ),
+            extra_data: Bytes::copy_from_slice(&block_header.extra_data),
+            mix_hash: fixed_bytes(&block_header.mix_hash, "mix_hash")?,
+            nonce: B64::from(block_header.nonce.to_be_bytes()),
+            base_fee_per_gas: block_header
+                .base_fee_per_gas
+                .as_ref()
+                .map(|base_fee_per_gas| u256_field(Some(base_fee_per_gas), "base_fee_per_gas"))
+                .transpose()?,
+            withdrawals_root: optional_hash(&block_header.withdrawals_root, "withdrawals_root")?,
+            blob_gas_used: block_header.blob_gas_used.map(U64::from),
+            excess_blob_gas: block_header.excess_blob_gas.map(U64::from),
+            parent_beacon_block_root: optional_hash(
+                &block_header.parent_beacon_root,
+                "parent_beacon_root",
+            )?,
+        })
+    }
+}
+
+impl From<&HexBlockHeader> for BlockHeader {
+    fn from(hex_header: &HexBlockHeader) -> Self {
+        let big_int = |value: &U256| BigInt {
+            bytes: value.to_be_bytes_trimmed_vec(),
+        };
+        let optional_hash =
+            |hash: &Option<B256>| hash.map(|hash| hash.to_vec()).unwrap_or_default();
+
+        Self {
+            parent_hash: hex_header.parent_hash.to_vec(),
+            uncle_hash: hex_header.sha3_uncles.to_vec(),
+            coinbase: hex_header.miner.to_vec(),
+            state_root: hex_header.state_root.to_vec(),
+            transactions_root: hex_header.transactions_root.to_vec(),
+            receipt_root: hex_header.receipts_root.to_vec(),
+            logs_bloom: hex_header.logs_bloom.to_vec(),
+            difficulty: Some(big_int(&hex_header.difficulty)),
+            total_difficulty: hex_header.total_difficulty.as_ref().map(big_int),
+            number: hex_header.number.to(),
+            gas_limit: hex_header.gas_limit.to(),
+            gas_used: hex_header.gas_used.to(),
+            timestamp: Some(Timestamp {
+                seconds: hex_header.timestamp.to::<u64>() as i64,
+                nanos: 0,
+            }),
+            extra_data: hex_header.extra_data.to_vec(),
+            mix_hash: hex_header.mix_hash.to_vec(),
+            nonce: u64::from_be_bytes(hex_header.nonce.0),
+            hash: hex_header.hash.to_vec(),
+            base_fee_per_gas: hex_header.base_fee_per_gas.as_ref().map(big_int),
+            withdrawals_root: optional_hash(&hex_header.withdrawals_root),
+            tx_dependency: None,
+            blob_gas_used: hex_header.blob_gas_used.map(|gas| gas.to()),
+            excess_blob_gas: hex_header.excess_blob_gas.map(|gas| gas.to()),
+            parent_beacon_root: optional_hash(&hex_header.parent_beacon_block_root),
+        }
+    }
+}
+
+/// Converts the bytes of a header `field` into fixed-size bytes, failing if the length does
+/// not match.
+fn fixed_bytes<const N: usize>(
+    bytes: &[u8],
+    field: &'static str,
+) -> Result<FixedBytes<N>, ProtosError> {
+    FixedBytes::try_from(bytes).map_err(|_| ProtosError::FieldConversion { field })
+}
+
+/// Converts an optional header `field` into a [`U256`], treating a missing value as zero and
+/// failing if it does not fit in 256 bits.
+fn u256_field(big_int: Option<&BigInt>, field: &'static str) -> Result<U256, ProtosError> {
+    big_int.map_or(Ok(U256::ZERO), |big_int| {
+        U256::try_from_be_slice(&big_int.bytes).ok_or(ProtosError::FieldConversion { field })
+    })
+}
+
 fn decode_block<M>(response: M) -> Result<Block, ProtosError>
 where
     M: MessageWithBlock,
@@ -139,7 +327,123 @@ impl TryFrom<Response> for Block {
     }
 }
 
+/// First block of the Byzantium hard fork on Ethereum mainnet.
+const BYZANTIUM_FORK_BLOCK: u64 = 4_370_000;
+
+/// First block of the Constantinople hard fork on Ethereum mainnet.
+const CONSTANTINOPLE_FORK_BLOCK: u64 = 7_280_000;
+
+/// First proof-of-stake block on Ethereum mainnet.
+const MERGE_BLOCK: u64 = 15_537_394;
+
 impl Block {
+    /// Calculates the reward paid to the miner of a pre-merge block, in wei.
+    ///
+    /// The reward is the sum of:
+    /// - the static block reward for the block's fork era: 5 ETH before Byzantium, 3 ETH from
+    ///   Byzantium and 2 ETH from Constantinople,
+    /// - 1/32 of the static block reward for each uncle included in the block,
+    /// - the transaction fees paid to the miner. From London onwards the burned base fee is
+    ///   excluded, leaving only the priority fee.
+    ///
+    /// Post-merge blocks carry no static or uncle reward, so only the fees are returned. Rewards
+    /// paid to the miners of the uncles themselves are not included.
+    pub fn block_reward(&self) -> Result<U256, ProtosError> {
+        let header = self.header()?;
+
+        let static_reward = U256::from(self.static_block_reward());
+        let uncle_inclusion_reward = static_reward / U256::from(32) * U256::from(self.uncles.len());
+
+        let base_fee_per_gas = header
+            .base_fee_per_gas
+            .as_ref()
+            .map(u128::try_from)
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut fees = U256::ZERO;
+        for trace in &self.transaction_traces {
+            let gas_price = trace
+                .gas_price
+                .as_ref()
+                .map(u128::try_from)
+                .transpose()?
+                .unwrap_or_default();
+            let priority_fee = gas_price.saturating_sub(base_fee_per_gas);
+            fees += U256::from(trace.gas_used) * U256::from(priority_fee);
+        }
+
+        Ok(static_reward + uncle_inclusion_reward + fees)
+    }
+
+    /// Heuristically extracts the payment made to the block's proposer, returning the
+    /// recipient and the amount in wei.
+    ///
+    /// Post-merge blocks built through a relay typically set the builder as the `coinbase` and end
+    /// with a transaction from the builder to the proposer's fee recipient. If the last transaction
+    /// of the block is such a transfer, its recipient and value are returned.
+    ///
+    /// Otherwise, the block is scanned from the end for the last successful transaction paying the
+    /// `coinbase` directly, including value moved to it by internal calls that were not reverted.
+    /// In that case the `coinbase` and the total value it received in that transaction are
+    /// returned.
+    ///
+    /// Returns `None` if the header is missing or no such payment is found.
+    pub fn builder_payment(&self) -> Option<(Address, U256)> {
+        let coinbase = Address::try_from(self.header().ok()?.coinbase.as_slice()).ok()?;
+
+        if let Some(last) = self.transaction_traces.last() {
+            let from = Address::try_from(last.from.as_slice()).ok();
+            let to = Address::try_from(last.to.as_slice()).ok();
+            let value = big_int_to_u256(last.value.as_ref());
+            if let (Some(from), Some(to)) = (from, to) {
+                if last.is_success() && from == coinbase && to != coinbase && !value.is_zero() {
+                    return Some((to, value));
+                }
+            }
+        }
+
+        self.transaction_traces
+            .iter()
+            .rev()
+            .filter(|trace| trace.is_success())
+            .find_map(|trace| {
+                let paid = if trace.calls.is_empty() {
+                    if trace.to.as_slice() == coinbase.as_slice() {
+                        big_int_to_u256(trace.value.as_ref())
+                    } else {
+                        U256::ZERO
+                    }
+                } else {
+                    trace
+                        .calls
+                        .iter()
+                        .filter(|call| {
+                            !call.state_reverted
+                                && call.address.as_slice() == coinbase.as_slice()
+                                && call.caller.as_slice() != coinbase.as_slice()
+                        })
+                        .map(|call| big_int_to_u256(call.value.as_ref()))
+                        .fold(U256::ZERO, |total, value| total + value)
+                };
+
+                (!paid.is_zero()).then_some((coinbase, paid))
+            })
+    }
+
+    /// Calculates the ommers hash of the block, the keccak256 hash of the RLP encoded list of
+    /// its uncle headers
+    ///
+    /// Post-merge blocks have no uncles, so their ommers hash is the hash of the empty list.
+    pub fn calculate_ommers_hash(&self) -> Result<B256, ProtosError> {
+        let ommers = self
+            .uncles
+            .iter()
+            .map(Header::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(calculate_ommers_root(&ommers))
+    }
+
     /// Calculates the trie receipt root of a given block receipts
     ///
     /// It uses the traces to aggregate receipts from blocks
@@ -158,11 +462,25 @@ impl Block {
         Ok(ordered_trie_root_with_encoder(&receipts, encoder))
     }
 
-    fn calculate_transaction_root(&self) -> Result<FixedBytes<32>, ProtosError> {
+    /// Calculates the trie transactions root of a given block
+    ///
+    /// The transactions are rebuilt from the block's transaction traces, so the result can be
+    /// compared against the transactions root recorded in the block header.
+    pub fn calculate_transaction_root(&self) -> Result<FixedBytes<32>, ProtosError> {
         let transactions = self.transaction_traces_to_signed_transactions()?;
         Ok(calculate_transaction_root(&transactions))
     }
 
+    /// Returns a content-addressable identifier for the block.
+    ///
+    /// Unlike the consensus block hash, which only commits to the header, this is the keccak256
+    /// hash of the full protobuf encoding of the block, including transaction traces and
+    /// receipts. Two blocks sharing a block hash but differing in trace detail therefore get
+    /// different content IDs, making this suitable as a deduplication or cache key.
+    pub fn content_id(&self) -> B256 {
+        keccak256(self.encode_to_vec())
+    }
+
     /// Converts the transaction traces of the current block into a vector of `FullReceipt` objects.
     ///
     /// # Arguments
@@ -202,17 +520,81 @@ impl Block {
         }
     }
 
+    /// Checks that the gas used recorded in the block header equals the cumulative gas used
+    /// recorded in the receipt of the last transaction. Blocks without transactions are
+    /// consistent by definition.
+    pub fn gas_used_is_consistent(&self) -> Result<bool, ProtosError> {
+        let header = self.header()?;
+        match self.transaction_traces.last() {
+            Some(trace) => Ok(trace.receipt()?.cumulative_gas_used == header.gas_used),
+            None => Ok(true),
+        }
+    }
+
     /// Returns a reference to the block header.
     pub fn header(&self) -> Result<&BlockHeader, ProtosError> {
         self.header.as_ref().ok_or(ProtosError::BlockHeaderMissing)
     }
 
-    fn is_pre_byzantium(&self) -> bool {
-        const BYZANTIUM_FORK_BLOCK: u64 = 4_370_000;
+    /// Tests whether `item`, such as a log address or topic, may be present in the block's
+    /// logs bloom.
+    ///
+    /// As with any bloom filter, `false` means the item is certainly absent from the block's
+    /// logs, while `true` may be a false positive.
+    pub fn logs_bloom_contains(&self, item: &[u8]) -> Result<bool, ProtosError> {
+        let logs_bloom = Bloom::from(fixed_bytes::<256>(
+            &self.header()?.logs_bloom,
+            "logs_bloom",
+        )?);
+        Ok(logs_bloom.contains_input(BloomInput::Raw(item)))
+    }
 
+    /// Returns the root of the parent beacon block recorded in the header, which links a
+    /// post-Dencun execution block to the consensus layer.
+    ///
+    /// Returns `None` for pre-Dencun blocks, which have no such field, or if the header is
+    /// missing or the field is malformed.
+    pub fn parent_beacon_block_root(&self) -> Option<B256> {
+        let header = self.header.as_ref()?;
+        B256::try_from(header.parent_beacon_root.as_slice()).ok()
+    }
+
+    fn is_pre_byzantium(&self) -> bool {
         self.number < BYZANTIUM_FORK_BLOCK
     }
 
+    /// Static block reward in wei for the fork era of this block.
+    fn static_block_reward(&self) -> u128 {
+        const WEI_PER_ETHER: u128 = 1_000_000_000_000_000_000;
+
+        match self.number {
+            n if n >= MERGE_BLOCK => 0,
+            n if n >= CONSTANTINOPLE_FORK_BLOCK => 2 * WEI_PER_ETHER,
+            n if n >= BYZANTIUM_FORK_BLOCK => 3 * WEI_PER_ETHER,
+            _ => 5 * WEI_PER_ETHER,
+        }
+    }
+
+    /// Checks if the ommers hash calculated using [`Self::calculate_ommers_hash`] matches
+    /// the block header's uncle hash field.
+    pub fn ommers_hash_is_verified(&self) -> bool {
+        let ommers_hash = match self.calculate_ommers_hash() {
+            Ok(ommers_hash) => ommers_hash,
+            Err(e) => {
+                error!("Failed to calculate ommers hash: {e}");
+                return false;
+            }
+        };
+
+        match self.verify_ommers_hash(ommers_hash.as_slice()) {
+            Ok(result) => result,
+            Err(e) => {
+                error!("Failed to verify ommers hash: {e}");
+                false
+            }
+        }
+    }
+
     /// Checks if the receipt root calculated using [`Self::calculate_receipt_root`] matches
     /// the block header's receipt root field.
     pub fn receipt_root_is_verified(&self) -> bool {
@@ -262,12 +644,27 @@ impl Block {
         }
     }
 
-    /// Checks if the hash of selected block header contents is equal to the hash
-    /// recorded in the block header. Returns `true` if they match, `false`
-    /// otherwise. The block hash is calculated using the ethportal-api Header method.
+    /// Tallies the transactions in this block by their [EIP-2718](https://eips.ethereum.org/EIPS/eip-2718)
+    /// transaction type, using the [`TxType`] conversion of each transaction trace.
+    pub fn transaction_type_counts(&self) -> Result<BTreeMap<TxType, usize>, ProtosError> {
+        let mut counts = BTreeMap::new();
+        for trace in &self.transaction_traces {
+            *counts.entry(TxType::try_from(trace)?).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Checks if the hash of the block header contents is equal to the hash recorded in the
+    /// block header, and in the block itself when present. Returns `true` if they match,
+    /// `false` otherwise. The block hash is the keccak256 hash of the RLP encoded header.
     pub fn block_hash_is_verified(&self) -> bool {
-        let header = Header::try_from(self).unwrap();
-        let block_hash = header.hash_slow();
+        let block_hash = match Header::try_from(self) {
+            Ok(header) => header.hash_slow(),
+            Err(e) => {
+                error!("Failed to convert block header: {e}");
+                return false;
+            }
+        };
 
         match self.verify_block_hash(block_hash.as_slice()) {
             Ok(result) => result,
@@ -278,11 +675,52 @@ impl Block {
         }
     }
 
+    /// Reports which of the block header's commitments are verified when the block is decoded
+    /// from a flat file.
+    ///
+    /// The decoder recomputes the block hash, receipt root and transaction root with the
+    /// `*_is_verified` methods, which all need the block header. The receipt and transaction
+    /// roots can only be rebuilt when every transaction has a supported [`TxType`], and the
+    /// receipt root also needs every transaction's receipt. The ommers hash is not checked by
+    /// the decoder, although [`Self::ommers_hash_is_verified`] can check it. The state root
+    /// cannot be verified, since recomputing it requires the full world state. The withdrawals
+    /// root, present from Shanghai onwards, cannot be either, since the block does not carry
+    /// the withdrawals themselves.
+    pub fn verification_coverage(&self) -> VerificationCoverage {
+        let has_header = self.header.is_some();
+        let has_withdrawals_root = self
+            .header
+            .as_ref()
+            .is_some_and(|header| !header.withdrawals_root.is_empty());
+        let tx_types_supported = self
+            .transaction_traces
+            .iter()
+            .all(|trace| TxType::try_from(trace).is_ok());
+        let has_receipts = self
+            .transaction_traces
+            .iter()
+            .all(|trace| trace.receipt().is_ok());
+
+        VerificationCoverage {
+            block_hash: has_header,
+            ommers_hash: false,
+            receipt_root: has_header && tx_types_supported && has_receipts,
+            transaction_root: has_header && tx_types_supported,
+            state_root: false,
+            withdrawals_root: has_withdrawals_root.then_some(false),
+        }
+    }
+
     /// Check if a value matches the receipt root hash recorded in the block header.
     fn verify_receipt_root(&self, other_receipt_root: &[u8]) -> Result<bool, ProtosError> {
         Ok(other_receipt_root == self.header()?.receipt_root.as_slice())
     }
 
+    /// Check if a value matches the uncle hash recorded in the block header.
+    fn verify_ommers_hash(&self, other_ommers_hash: &[u8]) -> Result<bool, ProtosError> {
+        Ok(other_ommers_hash == self.header()?.uncle_hash.as_slice())
+    }
+
     /// Check if a value matches the transaction root hash recorded in the block header.
     fn verify_transaction_root(&self, other_transaction_root: &[u8]) -> Result<bool, ProtosError> {
         Ok(other_transaction_root == self.header()?.transactions_root.as_slice())
@@ -290,10 +728,39 @@ impl Block {
 
     /// Check if a value matches the block hash recorded in the block header.
     fn verify_block_hash(&self, other_block_hash: &[u8]) -> Result<bool, ProtosError> {
-        Ok(other_block_hash == self.header()?.hash.as_slice())
+        let block_hash_matches = self.hash.is_empty() || other_block_hash == self.hash.as_slice();
+        Ok(block_hash_matches && other_block_hash == self.header()?.hash.as_slice())
     }
 }
 
+/// Which header commitments of a [`Block`] are verified when it is decoded, as reported by
+/// [`Block::verification_coverage`].
+///
+/// A `true` field means the commitment is recomputed from the block contents and compared
+/// against the header. A `false` field means the header value is taken on trust.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerificationCoverage {
+    /// Hash of the block header.
+    pub block_hash: bool,
+    /// Hash of the block's ommers (uncles).
+    pub ommers_hash: bool,
+    /// Root of the receipts trie.
+    pub receipt_root: bool,
+    /// Root of the transactions trie.
+    pub transaction_root: bool,
+    /// Root of the state trie.
+    pub state_root: bool,
+    /// Root of the withdrawals trie, `None` for blocks without one, before Shanghai.
+    pub withdrawals_root: Option<bool>,
+}
+
+/// Interprets an optional [`BigInt`] as a big-endian [`U256`], defaulting to zero.
+fn big_int_to_u256(big_int: Option<&BigInt>) -> U256 {
+    big_int
+        .and_then(|big_int| U256::try_from_be_slice(&big_int.bytes))
+        .unwrap_or_default()
+}
+
 /// Work with the [`reth_primitives::ReceiptWithBloom`] combined with the matching state root.
 pub struct FullReceipt {
     receipt: ReceiptWithBloom,
@@ -352,6 +819,20 @@ impl TransactionReceipt {
 }
 
 impl FullReceipt {
+    /// Encodes the receipt as committed to by the receipts root of the block with the given
+    /// number, writing the result to `out`.
+    ///
+    /// Receipts of blocks before the Byzantium fork hold the post-transaction state root, and
+    /// later receipts hold the transaction status, with typed transactions prefixed by their
+    /// type as defined by EIP-2718.
+    pub fn encode(&self, block_number: u64, out: &mut Vec<u8>) {
+        if block_number < BYZANTIUM_FORK_BLOCK {
+            self.encode_pre_byzantium_receipt(out)
+        } else {
+            self.encode_byzantium_and_later_receipt(out)
+        }
+    }
+
     /// Pre-Byzantium: encode header values and additional receipt data
     fn encode_pre_byzantium_receipt(&self, encoded: &mut Vec<u8>) {
         // Worried about determinism and the order of calling `encode` on the fields,
@@ -418,7 +899,7 @@ impl HasNumberOrSlot for Block {
 mod tests {
     use alloy_consensus::Header;
 
-    use crate::ethereum_v2::BlockHeader;
+    use crate::ethereum_v2::{BlockHeader, Call};
 
     use super::*;
 
@@ -449,6 +930,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_block_to_header_field_errors() {
+        let block_header: BlockHeader = serde_json::from_str(BLOCK).unwrap();
+        let field_error = |block_header: BlockHeader| match Header::try_from(&block_header) {
+            Err(ProtosError::FieldConversion { field }) => field,
+            other => panic!("expected a field conversion error, got {other:?}"),
+        };
+
+        assert!(matches!(
+            Header::try_from(&Block::default()),
+            Err(ProtosError::BlockHeaderMissing)
+        ));
+        assert_eq!(
+            field_error(BlockHeader {
+                difficulty: None,
+                ..block_header.clone()
+            }),
+            "difficulty"
+        );
+        assert_eq!(
+            field_error(BlockHeader {
+                timestamp: None,
+                ..block_header.clone()
+            }),
+            "timestamp"
+        );
+        assert_eq!(
+            field_error(BlockHeader {
+                parent_hash: vec![0; 31],
+                ..block_header.clone()
+            }),
+            "parent_hash"
+        );
+        assert_eq!(
+            field_error(BlockHeader {
+                coinbase: vec![],
+                ..block_header.clone()
+            }),
+            "coinbase"
+        );
+        assert_eq!(
+            field_error(BlockHeader {
+                parent_beacon_root: vec![0; 20],
+                ..block_header
+            }),
+            "parent_beacon_root"
+        );
+    }
+
     #[test]
     fn test_block_hash_verification() {
         let block_header: BlockHeader = serde_json::from_str(BLOCK).unwrap();
@@ -458,7 +988,388 @@ mod tests {
             ..Default::default()
         };
 
-        assert!(block.block_hash_is_verified())
+        assert!(block.block_hash_is_verified());
+
+        let block = Block {
+            hash: block.header.as_ref().unwrap().hash.clone(),
+            ..block
+        };
+        assert!(block.block_hash_is_verified());
+
+        let mut tampered_header = block.clone();
+        tampered_header.header.as_mut().unwrap().gas_used += 1;
+        assert!(!tampered_header.block_hash_is_verified());
+
+        let mut tampered_hash = block.clone();
+        tampered_hash.hash[0] ^= 0xff;
+        assert!(!tampered_hash.block_hash_is_verified());
+
+        let mut missing_timestamp = block;
+        missing_timestamp.header.as_mut().unwrap().timestamp = None;
+        assert!(!missing_timestamp.block_hash_is_verified());
+    }
+
+    #[test]
+    fn test_hex_block_header_field_errors() {
+        let block_header: BlockHeader = serde_json::from_str(BLOCK).unwrap();
+        let too_large = Some(BigInt { bytes: vec![1; 33] });
+        let field_error = |block_header: BlockHeader| match HexBlockHeader::try_from(&block_header)
+        {
+            Err(ProtosError::FieldConversion { field }) => field,
+            other => panic!("expected a field conversion error, got {other:?}"),
+        };
+
+        assert_eq!(
+            field_error(BlockHeader {
+                difficulty: too_large.clone(),
+                ..block_header.clone()
+            }),
+            "difficulty"
+        );
+        assert_eq!(
+            field_error(BlockHeader {
+                total_difficulty: too_large.clone(),
+                ..block_header.clone()
+            }),
+            "total_difficulty"
+        );
+        assert_eq!(
+            field_error(BlockHeader {
+                base_fee_per_gas: too_large,
+                ..block_header
+            }),
+            "base_fee_per_gas"
+        );
+    }
+
+    #[test]
+    fn test_hex_block_header_round_trip() {
+        let block_header: BlockHeader = serde_json::from_str(BLOCK).unwrap();
+
+        let hex_header = HexBlockHeader::try_from(&block_header).unwrap();
+        let json = serde_json::to_string(&hex_header).unwrap();
+        assert!(json.contains(
+            r#""hash":"0xf218f8b4f7879b1c4a44b658a32d4a338db85c85c2916229d8b1c7728b448382""#
+        ));
+        assert!(json.contains(r#""number":"0x139c2da""#));
+        assert!(json.contains(r#""nonce":"0x0000000000000000""#));
+
+        let decoded: HexBlockHeader = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, hex_header);
+
+        let round_tripped = BlockHeader::from(&decoded);
+        assert_eq!(round_tripped.hash, block_header.hash);
+        assert_eq!(
+            round_tripped.total_difficulty,
+            block_header.total_difficulty
+        );
+        assert_eq!(
+            Header::try_from(&round_tripped).unwrap(),
+            Header::try_from(&block_header).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verification_coverage() {
+        use crate::ethereum_v2::transaction_trace::Type;
+
+        let block_header: BlockHeader = serde_json::from_str(BLOCK).unwrap();
+        let mut block = Block {
+            header: Some(block_header),
+            ..Default::default()
+        };
+
+        let coverage = block.verification_coverage();
+        assert!(coverage.block_hash);
+        assert!(!coverage.ommers_hash);
+        assert!(coverage.receipt_root);
+        assert!(coverage.transaction_root);
+        assert!(!coverage.state_root);
+        assert_eq!(coverage.withdrawals_root, Some(false));
+
+        // Blocks from before Shanghai have no withdrawals root to verify.
+        block.header.as_mut().unwrap().withdrawals_root.clear();
+        assert_eq!(block.verification_coverage().withdrawals_root, None);
+
+        // Transactions without a mainnet equivalent cannot be rebuilt into either trie.
+        block.transaction_traces.push(TransactionTrace {
+            r#type: Type::TrxTypeArbitrumDeposit as i32,
+            receipt: Some(Default::default()),
+            ..Default::default()
+        });
+        let coverage = block.verification_coverage();
+        assert!(coverage.block_hash);
+        assert!(!coverage.receipt_root);
+        assert!(!coverage.transaction_root);
+
+        // Neither can a receipt root with a missing receipt.
+        block.transaction_traces[0] = TransactionTrace::default();
+        let coverage = block.verification_coverage();
+        assert!(!coverage.receipt_root);
+        assert!(coverage.transaction_root);
+
+        // Nothing can be verified without a header.
+        block.header = None;
+        let coverage = block.verification_coverage();
+        assert!(!coverage.block_hash && !coverage.ommers_hash && !coverage.transaction_root);
+    }
+
+    #[test]
+    fn test_ommers_hash_without_uncles() {
+        let block_header: BlockHeader = serde_json::from_str(BLOCK).unwrap();
+        let block = Block {
+            header: Some(block_header),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            block.calculate_ommers_hash().unwrap(),
+            alloy_consensus::EMPTY_OMMER_ROOT_HASH
+        );
+        assert!(block.ommers_hash_is_verified());
+    }
+
+    #[test]
+    fn test_ommers_hash_with_uncles() {
+        let uncle: BlockHeader = serde_json::from_str(BLOCK).unwrap();
+        let encoded_uncle = alloy_rlp::encode(Header::try_from(&uncle).unwrap());
+        let mut encoded_ommers = Vec::new();
+        RlpHeader {
+            list: true,
+            payload_length: encoded_uncle.len(),
+        }
+        .encode(&mut encoded_ommers);
+        encoded_ommers.extend_from_slice(&encoded_uncle);
+        let ommers_hash = keccak256(&encoded_ommers);
+
+        let mut block = Block {
+            header: Some(uncle.clone()),
+            uncles: vec![uncle],
+            ..Default::default()
+        };
+        assert_eq!(block.calculate_ommers_hash().unwrap(), ommers_hash);
+        assert!(!block.ommers_hash_is_verified());
+
+        block.header.as_mut().unwrap().uncle_hash = ommers_hash.to_vec();
+        assert!(block.ommers_hash_is_verified());
+    }
+
+    #[test]
+    fn test_calculate_transaction_root() {
+        let block_header: BlockHeader = serde_json::from_str(BLOCK).unwrap();
+        let mut block = Block {
+            header: Some(block_header),
+            ..Default::default()
+        };
+
+        let tx_root = block.calculate_transaction_root().unwrap();
+        assert_eq!(tx_root, alloy_consensus::EMPTY_ROOT_HASH);
+        assert!(!block.transaction_root_is_verified());
+
+        block.header.as_mut().unwrap().transactions_root = tx_root.to_vec();
+        assert!(block.transaction_root_is_verified());
+    }
+
+    #[test]
+    fn test_transaction_type_counts() {
+        use crate::ethereum_v2::transaction_trace::Type;
+
+        let block = Block {
+            transaction_traces: vec![
+                create_test_trace(Type::TrxTypeLegacy as i32),
+                create_test_trace(Type::TrxTypeDynamicFee as i32),
+                create_test_trace(Type::TrxTypeDynamicFee as i32),
+                create_test_trace(Type::TrxTypeBlob as i32),
+            ],
+            ..Default::default()
+        };
+
+        let counts = block.transaction_type_counts().unwrap();
+
+        assert_eq!(counts.get(&TxType::Legacy), Some(&1));
+        assert_eq!(counts.get(&TxType::Eip1559), Some(&2));
+        assert_eq!(counts.get(&TxType::Eip4844), Some(&1));
+        assert_eq!(counts.get(&TxType::Eip2930), None);
+    }
+
+    #[test]
+    fn test_block_reward() {
+        const WEI_PER_ETHER: u128 = 1_000_000_000_000_000_000;
+
+        let frontier_block = Block {
+            number: 1,
+            header: Some(BlockHeader::default()),
+            uncles: vec![BlockHeader::default()],
+            ..Default::default()
+        };
+        assert_eq!(
+            frontier_block.block_reward().unwrap(),
+            U256::from(5 * WEI_PER_ETHER + 5 * WEI_PER_ETHER / 32)
+        );
+
+        let byzantium_block = Block {
+            number: BYZANTIUM_FORK_BLOCK,
+            header: Some(BlockHeader::default()),
+            ..Default::default()
+        };
+        assert_eq!(
+            byzantium_block.block_reward().unwrap(),
+            U256::from(3 * WEI_PER_ETHER)
+        );
+
+        let constantinople_block = Block {
+            number: CONSTANTINOPLE_FORK_BLOCK,
+            header: Some(BlockHeader::default()),
+            transaction_traces: vec![TransactionTrace {
+                gas_used: 21_000,
+                gas_price: Some(BigInt { bytes: vec![10] }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            constantinople_block.block_reward().unwrap(),
+            U256::from(2 * WEI_PER_ETHER + 210_000)
+        );
+
+        let post_merge_block = Block {
+            number: MERGE_BLOCK,
+            header: Some(BlockHeader::default()),
+            ..Default::default()
+        };
+        assert_eq!(post_merge_block.block_reward().unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn test_content_id() {
+        let block_header: BlockHeader = serde_json::from_str(BLOCK).unwrap();
+
+        let block = Block {
+            hash: block_header.hash.clone(),
+            header: Some(block_header),
+            ..Default::default()
+        };
+        let mut detailed_block = block.clone();
+        detailed_block.transaction_traces = vec![create_test_trace(0)];
+
+        assert_eq!(block.content_id(), block.clone().content_id());
+        assert_ne!(block.content_id(), detailed_block.content_id());
+    }
+
+    #[test]
+    fn test_builder_payment() {
+        let builder = Address::repeat_byte(0xb1);
+        let proposer = Address::repeat_byte(0xfe);
+        let searcher = Address::repeat_byte(0x5e);
+
+        let header = BlockHeader {
+            coinbase: builder.to_vec(),
+            ..Default::default()
+        };
+
+        let bribe = TransactionTrace {
+            status: 1,
+            from: searcher.to_vec(),
+            calls: vec![Call {
+                caller: searcher.to_vec(),
+                address: builder.to_vec(),
+                value: Some(BigInt { bytes: vec![7] }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let block = Block {
+            header: Some(header.clone()),
+            transaction_traces: vec![bribe.clone()],
+            ..Default::default()
+        };
+        assert_eq!(block.builder_payment(), Some((builder, U256::from(7))));
+
+        let payment = TransactionTrace {
+            status: 1,
+            from: builder.to_vec(),
+            to: proposer.to_vec(),
+            value: Some(BigInt { bytes: vec![1, 0] }),
+            ..Default::default()
+        };
+
+        let block = Block {
+            header: Some(header),
+            transaction_traces: vec![bribe, payment],
+            ..Default::default()
+        };
+        assert_eq!(block.builder_payment(), Some((proposer, U256::from(256))));
+    }
+
+    #[test]
+    fn test_logs_bloom_contains() {
+        let log_address = Address::from([0x11; 20]);
+        let log_topic = B256::from([0x22; 32]);
+        let mut logs_bloom = Bloom::ZERO;
+        logs_bloom.accrue(BloomInput::Raw(log_address.as_slice()));
+        logs_bloom.accrue(BloomInput::Raw(log_topic.as_slice()));
+
+        let block = Block {
+            header: Some(BlockHeader {
+                logs_bloom: logs_bloom.to_vec(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert!(block.logs_bloom_contains(log_address.as_slice()).unwrap());
+        assert!(block.logs_bloom_contains(log_topic.as_slice()).unwrap());
+        assert!(!block
+            .logs_bloom_contains(Address::from([0x33; 20]).as_slice())
+            .unwrap());
+
+        assert!(matches!(
+            Block::default().logs_bloom_contains(log_address.as_slice()),
+            Err(ProtosError::BlockHeaderMissing)
+        ));
+    }
+
+    #[test]
+    fn test_parent_beacon_block_root() {
+        let dencun_block = Block {
+            header: Some(serde_json::from_str(BLOCK).unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(
+            dencun_block.parent_beacon_block_root(),
+            Some(alloy_primitives::b256!(
+                "c8b270f70fdbdf28dd9e38cd0d9b09442089c951c36fef5613ff93c68ccbe822"
+            ))
+        );
+
+        let pre_dencun_block = Block {
+            header: Some(BlockHeader {
+                number: 17034870,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(pre_dencun_block.parent_beacon_block_root(), None);
+        assert_eq!(Block::default().parent_beacon_block_root(), None);
+    }
+
+    #[test]
+    fn test_gas_used_is_consistent() {
+        let mut block = Block {
+            header: Some(BlockHeader {
+                gas_used: 21000,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(block.gas_used_is_consistent().unwrap());
+
+        block.transaction_traces = vec![create_test_trace(0)];
+        assert!(block.gas_used_is_consistent().unwrap());
+
+        block.header.as_mut().unwrap().gas_used = 42000;
+        assert!(!block.gas_used_is_consistent().unwrap());
     }
 
     static BLOCK: &str = r###"
@@ -563,6 +1474,32 @@ mod tests {
         assert_eq!(encoded[0], TxType::Eip4844 as u8);
     }
 
+    #[test]
+    fn receipt_encoding_differs_by_fork() {
+        use crate::ethereum_v2::transaction_trace::Type;
+
+        let trace = create_test_trace(Type::TrxTypeLegacy as i32);
+        let full_receipt = FullReceipt::try_from(&trace).unwrap();
+
+        let mut pre_byzantium = Vec::new();
+        full_receipt.encode(BYZANTIUM_FORK_BLOCK - 1, &mut pre_byzantium);
+        let mut byzantium = Vec::new();
+        full_receipt.encode(BYZANTIUM_FORK_BLOCK, &mut byzantium);
+
+        // Both are RLP lists with a two byte length
+        assert_eq!(pre_byzantium[0], 0xf9);
+        assert_eq!(byzantium[0], 0xf9);
+        // The 32 byte state root, prefixed by 0xa0, is replaced by the single byte status
+        assert_eq!(pre_byzantium.len(), byzantium.len() + 32);
+        assert_eq!(pre_byzantium[3], 0xa0);
+        assert_eq!(pre_byzantium[4..36], [1u8; 32]);
+        assert_eq!(byzantium[3], 0x01);
+
+        let mut encoded = Vec::new();
+        full_receipt.encode_pre_byzantium_receipt(&mut encoded);
+        assert_eq!(encoded, pre_byzantium);
+    }
+
     #[test]
     fn full_receipt_stores_correct_tx_type() {
         use crate::ethereum_v2::transaction_trace::Type;
@@ -1,12 +1,34 @@
 // SPDX-FileCopyrightText: 2024- Semiotic AI, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use alloy_primitives::{hex, Address, Bytes, B256};
+use alloy_primitives::{b256, hex, Address, Bytes, B256, U256};
 use reth_primitives::LogData;
 
 use crate::error::ProtosError;
 
-use super::Log;
+use super::{Block, Log};
+
+/// Topic 0 of an ERC-20 `Transfer(address,address,uint256)` event, i.e. the keccak256 hash of
+/// the event signature.
+pub const ERC20_TRANSFER_TOPIC: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+/// An ERC-20 `Transfer` event decoded from a transaction receipt log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Erc20Transfer {
+    /// Address of the token contract that emitted the event.
+    pub token: Address,
+    /// Sender of the tokens.
+    pub from: Address,
+    /// Recipient of the tokens.
+    pub to: Address,
+    /// Amount of tokens transferred, in the token's base unit.
+    pub value: U256,
+    /// Index of the emitting transaction within the block.
+    pub transaction_index: u32,
+    /// Index of the log within the block.
+    pub log_index: u32,
+}
 
 impl TryFrom<&Log> for alloy_primitives::Log {
     type Error = ProtosError;
@@ -36,6 +58,64 @@ impl TryFrom<&Log> for Address {
     }
 }
 
+impl Block {
+    /// Returns every receipt log in the block, in block order, paired with the index of the
+    /// transaction that emitted it.
+    ///
+    /// Fails with [`ProtosError::TransactionTraceMissingReceipt`] if a transaction trace has
+    /// no receipt.
+    pub fn all_logs(&self) -> Result<Vec<(u64, alloy_primitives::Log)>, ProtosError> {
+        let mut logs = Vec::new();
+
+        for trace in &self.transaction_traces {
+            for log in &trace.receipt()?.logs {
+                logs.push((
+                    u64::from(trace.index),
+                    alloy_primitives::Log::try_from(log)?,
+                ));
+            }
+        }
+
+        Ok(logs)
+    }
+
+    /// Extracts all ERC-20 `Transfer` events from the receipt logs of this block.
+    ///
+    /// Only logs recorded in transaction receipts are considered, so events emitted by reverted
+    /// calls are excluded. ERC-721 transfers share the event signature but index the token ID
+    /// as a fourth topic; they are skipped by requiring exactly three topics and a 32-byte
+    /// value in the log data.
+    ///
+    /// Fails with [`ProtosError::TransactionTraceMissingReceipt`] if a transaction trace has
+    /// no receipt.
+    pub fn erc20_transfers(&self) -> Result<Vec<Erc20Transfer>, ProtosError> {
+        let mut transfers = Vec::new();
+
+        for trace in &self.transaction_traces {
+            for log in &trace.receipt()?.logs {
+                if log.topics.len() != 3
+                    || log.topics[0].as_slice() != ERC20_TRANSFER_TOPIC.as_slice()
+                    || log.data.len() != 32
+                {
+                    continue;
+                }
+
+                let topics = log.to_topics()?;
+                transfers.push(Erc20Transfer {
+                    token: Address::try_from(log)?,
+                    from: Address::from_word(topics[1]),
+                    to: Address::from_word(topics[2]),
+                    value: U256::from_be_slice(&log.data),
+                    transaction_index: trace.index,
+                    log_index: log.block_index,
+                });
+            }
+        }
+
+        Ok(transfers)
+    }
+}
+
 impl Log {
     fn to_topics(&self) -> Result<Vec<B256>, ProtosError> {
         fn to_b256(slice: &[u8]) -> Result<B256, ProtosError> {
@@ -148,4 +228,100 @@ mod tests {
         assert_eq!(alloy_log.data.data.as_ref(), fake_log.data.as_slice());
         assert_eq!(alloy_log.data.topics().len(), fake_log.topics.len());
     }
+
+    #[test]
+    fn test_all_logs() {
+        use crate::ethereum_v2::{TransactionReceipt, TransactionTrace};
+
+        let trace_with_logs = |index: u32, logs: Vec<Log>| TransactionTrace {
+            index,
+            receipt: Some(TransactionReceipt {
+                logs,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut block = Block {
+            transaction_traces: vec![
+                trace_with_logs(0, vec![create_fake_log(), create_fake_log()]),
+                trace_with_logs(1, vec![]),
+                trace_with_logs(2, vec![create_fake_log()]),
+            ],
+            ..Default::default()
+        };
+
+        let logs = block.all_logs().unwrap();
+        assert_eq!(logs.len(), 3);
+        assert_eq!(
+            logs.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            vec![0, 0, 2]
+        );
+        assert_eq!(logs[0].1.address, Address::from([0x11; 20]));
+
+        block.transaction_traces.push(TransactionTrace::default());
+        assert!(matches!(
+            block.all_logs(),
+            Err(ProtosError::TransactionTraceMissingReceipt)
+        ));
+    }
+
+    #[test]
+    fn test_erc20_transfers() {
+        use crate::ethereum_v2::{TransactionReceipt, TransactionTrace};
+
+        let from = Address::repeat_byte(0xaa);
+        let to = Address::repeat_byte(0xbb);
+        let transfer = create_fake_log_with(
+            vec![0x11; 20],
+            U256::from(1_000u64).to_be_bytes_vec(),
+            vec![
+                ERC20_TRANSFER_TOPIC.to_vec(),
+                from.into_word().to_vec(),
+                to.into_word().to_vec(),
+            ],
+        );
+        let nft_transfer = create_fake_log_with(
+            vec![0x22; 20],
+            vec![],
+            vec![
+                ERC20_TRANSFER_TOPIC.to_vec(),
+                from.into_word().to_vec(),
+                to.into_word().to_vec(),
+                vec![0x01; 32],
+            ],
+        );
+
+        let mut block = Block {
+            transaction_traces: vec![TransactionTrace {
+                index: 3,
+                receipt: Some(TransactionReceipt {
+                    logs: vec![transfer, nft_transfer, create_fake_log()],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let transfers = block.erc20_transfers().unwrap();
+
+        assert_eq!(
+            transfers,
+            vec![Erc20Transfer {
+                token: Address::from([0x11; 20]),
+                from,
+                to,
+                value: U256::from(1_000u64),
+                transaction_index: 3,
+                log_index: 0,
+            }]
+        );
+
+        block.transaction_traces.push(TransactionTrace::default());
+        assert!(matches!(
+            block.erc20_transfers(),
+            Err(ProtosError::TransactionTraceMissingReceipt)
+        ));
+    }
 }
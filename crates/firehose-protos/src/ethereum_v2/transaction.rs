@@ -4,7 +4,10 @@
 
 use std::fmt::Display;
 
-use alloy_consensus::{EthereumTxEnvelope, Signed, TxEip1559, TxEip2930, TxEip4844, TxLegacy};
+use alloy_consensus::{
+    transaction::SignerRecoverable, EthereumTxEnvelope, Signed, TxEip1559, TxEip2930, TxEip4844,
+    TxLegacy,
+};
 use alloy_eip2930::{AccessList, AccessListItem};
 use alloy_primitives::{
     hex, Address, Bytes, ChainId, FixedBytes, Signature, TxKind, Uint, B256, U128, U256,
@@ -16,24 +19,37 @@ use crate::error::ProtosError;
 
 use super::{transaction_trace::Type, BigInt, CallType, TransactionReceipt, TransactionTrace};
 
-impl From<Type> for TxType {
-    fn from(tx_type: Type) -> Self {
+/// Converts a Firehose transaction type into its Ethereum mainnet [`TxType`].
+///
+/// This replaces the former infallible `From<Type> for TxType`, which panicked on Arbitrum and
+/// Optimism types. A deprecated `From` cannot be kept next to it, as it would conflict with the
+/// blanket `TryFrom` implementation, so callers of `TxType::from(ty)` should use
+/// `TxType::try_from(ty)` and handle [`ProtosError::UnsupportedTxType`].
+impl TryFrom<Type> for TxType {
+    type Error = ProtosError;
+
+    fn try_from(tx_type: Type) -> Result<Self, Self::Error> {
         use TxType::*;
         use Type::*;
 
         match tx_type {
-            TrxTypeLegacy => Legacy,
-            TrxTypeAccessList => Eip2930,
-            TrxTypeDynamicFee => Eip1559,
-            TrxTypeBlob => Eip4844,
-            TrxTypeArbitrumDeposit => unimplemented!(),
-            TrxTypeArbitrumUnsigned => unimplemented!(),
-            TrxTypeArbitrumContract => unimplemented!(),
-            TrxTypeArbitrumRetry => unimplemented!(),
-            TrxTypeArbitrumSubmitRetryable => unimplemented!(),
-            TrxTypeArbitrumInternal => unimplemented!(),
-            TrxTypeArbitrumLegacy => unimplemented!(),
-            TrxTypeOptimismDeposit => unimplemented!(),
+            TrxTypeLegacy => Ok(Legacy),
+            TrxTypeAccessList => Ok(Eip2930),
+            TrxTypeDynamicFee => Ok(Eip1559),
+            TrxTypeBlob => Ok(Eip4844),
+            // Arbitrum and Optimism transaction types have no Ethereum mainnet equivalent.
+            // Optimism deposits cannot be rebuilt from a trace either: the trace carries no
+            // source hash, mint or system transaction flag, which the deposit hash commits to.
+            TrxTypeArbitrumDeposit
+            | TrxTypeArbitrumUnsigned
+            | TrxTypeArbitrumContract
+            | TrxTypeArbitrumRetry
+            | TrxTypeArbitrumSubmitRetryable
+            | TrxTypeArbitrumInternal
+            | TrxTypeArbitrumLegacy
+            | TrxTypeOptimismDeposit => Err(ProtosError::UnsupportedTxType(
+                tx_type.as_str_name().to_string(),
+            )),
         }
     }
 }
@@ -46,25 +62,177 @@ pub const CHAIN_ID: ChainId = 1;
 /// In Ethereum, the `v` value within a transaction's signature component can indicate whether the transaction
 /// is a legacy (pre-EIP-155) transaction or an EIP-155 transaction that includes a chain ID. Legacy transactions
 /// have `v` values of `27` or `28`, which do not encode a chain ID. For such transactions, this function returns `None`.
-/// For non-legacy transactions where `v` encodes a chain ID, this function returns the constant mainnet chain ID.
+/// For non-legacy transactions where `v` encodes a chain ID, this function returns the given chain ID.
 ///
-fn get_legacy_chain_id(trace: &TransactionTrace) -> Option<ChainId> {
+fn get_legacy_chain_id(trace: &TransactionTrace, chain_id: ChainId) -> Option<ChainId> {
     let v = trace.v();
     if v == 27 || v == 28 {
         None
     } else {
-        Some(CHAIN_ID)
+        Some(chain_id)
     }
 }
 
 impl TransactionTrace {
+    /// Converts the trace into a signed transaction for the network with the given chain ID.
+    ///
+    /// The `TryFrom<&TransactionTrace>` conversions assume Ethereum mainnet, see [`CHAIN_ID`].
+    /// Use this method for traces from other EVM networks, so that the transaction, and
+    /// therefore its signature hash, commits to the right chain ID.
+    pub fn to_signed_with_chain_id(
+        &self,
+        chain_id: ChainId,
+    ) -> Result<EthereumTxEnvelope<TxEip4844>, ProtosError> {
+        let tx = self.to_transaction(chain_id)?;
+        let signature = Signature::try_from(self)?;
+        let hash = FixedBytes::<32>::from_slice(self.hash.as_slice());
+
+        let envelope = match tx {
+            Transaction::Legacy(tx) => {
+                EthereumTxEnvelope::Legacy(Signed::new_unchecked(tx, signature, hash))
+            }
+            Transaction::Eip2930(tx) => {
+                EthereumTxEnvelope::Eip2930(Signed::new_unchecked(tx, signature, hash))
+            }
+            Transaction::Eip1559(tx) => {
+                EthereumTxEnvelope::Eip1559(Signed::new_unchecked(tx, signature, hash))
+            }
+            Transaction::Eip4844(tx) => {
+                EthereumTxEnvelope::Eip4844(Signed::new_unchecked(tx, signature, hash))
+            }
+            Transaction::Eip7702(_) => {
+                return Err(ProtosError::TxTypeConversion(
+                    "Eip7702 not supported".to_string(),
+                ));
+            }
+        };
+
+        Ok(envelope)
+    }
+
+    fn to_transaction(&self, chain_id: ChainId) -> Result<Transaction, ProtosError> {
+        let tx_type = reth_primitives::TxType::try_from(self)?;
+        let nonce = self.nonce;
+        let gas_price = get_u128_or_default(&self.gas_price)?;
+        let gas_limit = self.gas_limit;
+        let to = TxKind::try_from(self)?;
+        let value = Uint::from(get_u128_or_default(&self.value)?);
+        let input = Bytes::copy_from_slice(self.input.as_slice());
+
+        let transaction: Transaction = match tx_type {
+            TxType::Legacy => Transaction::Legacy(TxLegacy {
+                chain_id: get_legacy_chain_id(self, chain_id),
+                nonce,
+                gas_price,
+                gas_limit,
+                to,
+                value,
+                input,
+            }),
+            TxType::Eip2930 => Transaction::Eip2930(TxEip2930 {
+                chain_id,
+                nonce,
+                gas_price,
+                gas_limit,
+                to,
+                value,
+                access_list: AccessList::try_from(self)?,
+                input,
+            }),
+            TxType::Eip1559 => Transaction::Eip1559(TxEip1559 {
+                chain_id,
+                nonce,
+                gas_limit,
+                max_fee_per_gas: get_u128_or_default(&self.max_fee_per_gas)?,
+                max_priority_fee_per_gas: get_u128_or_default(&self.max_priority_fee_per_gas)?,
+                to,
+                value,
+                access_list: AccessList::try_from(self)?,
+                input,
+            }),
+            TxType::Eip4844 => Transaction::Eip4844(TxEip4844 {
+                chain_id,
+                nonce,
+                gas_limit,
+                max_fee_per_gas: get_u128_or_default(&self.max_fee_per_gas)?,
+                max_priority_fee_per_gas: get_u128_or_default(&self.max_priority_fee_per_gas)?,
+                to: Address::try_from(self)?,
+                value,
+                access_list: AccessList::try_from(self)?,
+                blob_versioned_hashes: repeated_bytes_to_b256(&self.blob_hashes)?,
+                max_fee_per_blob_gas: get_u128_or_default(&self.blob_gas_fee_cap)?,
+                input,
+            }),
+            // The StreamingFast Ethereum Firehose block protobuf definition does not include
+            // the `access_list` entry for this type, which distinguishes it from the
+            // `Eip1559` type.
+            TxType::Eip7702 => unimplemented!(),
+        };
+
+        Ok(transaction)
+    }
+
+    /// Recovers the sender address from the transaction signature.
+    ///
+    /// The transaction is rebuilt from the trace and the signer is recovered from its signature
+    /// hash. EIP-155 legacy transactions are rebuilt for the chain ID encoded in their signature,
+    /// see [`Self::eip155_chain_id`], while other transactions assume Ethereum mainnet. Use
+    /// [`Self::recover_sender_with_chain_id`] for typed transactions from other EVM networks.
+    /// When the trace's `from` field is populated, the recovered address must match it,
+    /// otherwise [`ProtosError::SenderMismatch`] is returned.
+    pub fn recover_sender(&self) -> Result<Address, ProtosError> {
+        self.recover_sender_with_chain_id(self.eip155_chain_id().unwrap_or(CHAIN_ID))
+    }
+
+    /// Recovers the sender address from the transaction signature, rebuilding the transaction
+    /// for the network with the given chain ID.
+    ///
+    /// See [`Self::recover_sender`] and [`Self::to_signed_with_chain_id`].
+    pub fn recover_sender_with_chain_id(&self, chain_id: ChainId) -> Result<Address, ProtosError> {
+        let envelope = self.to_signed_with_chain_id(chain_id)?;
+        let sender = envelope
+            .recover_signer()
+            .map_err(ProtosError::SenderRecovery)?;
+
+        if !self.from.is_empty() && self.from.as_slice() != sender.as_slice() {
+            return Err(ProtosError::SenderMismatch {
+                from: hex::encode(&self.from),
+                recovered: sender,
+            });
+        }
+
+        Ok(sender)
+    }
+
+    /// Returns the chain ID encoded in the `v` value of an EIP-155 signature, `(v - 35) / 2`.
+    ///
+    /// Returns `None` for pre-EIP-155 signatures, with a `v` of `27` or `28`, and for the
+    /// `y_parity` values of typed transactions.
+    pub fn eip155_chain_id(&self) -> Option<ChainId> {
+        if self.v.len() > 8 {
+            return None;
+        }
+        let v = self.v();
+
+        (v >= 35).then(|| (v - 35) / 2)
+    }
+
+    /// Returns true if the transaction's signature commits to a chain ID, so that it cannot be
+    /// replayed on another chain.
+    ///
+    /// Legacy transactions are replay protected when signed following EIP-155, with a `v` of
+    /// `35` or more. Typed transactions always include the chain ID they are signed for.
+    pub fn is_replay_protected(&self) -> bool {
+        self.r#type() != Type::TrxTypeLegacy || self.eip155_chain_id().is_some()
+    }
+
     /// Returns true if the transaction's status is successful.
     pub(crate) fn is_success(&self) -> bool {
         self.status == 1
     }
 
     fn parity(&self) -> Result<bool, ProtosError> {
-        // Extract the first byte of the V value (Ethereum's V value).
+        // Interpret the V value (Ethereum's V value) as an integer.
         let v = self.v();
 
         let parity = match v {
@@ -74,8 +242,8 @@ impl TransactionTrace {
             // V values 27 and 28 are commonly used in Ethereum and indicate Y parity.
             27 | 28 => v - 27 == 1,
 
-            // V values 37 and 38 are less common but still valid and represent Y parity.
-            37 | 38 => v - 37 == 1,
+            // EIP-155 V values encode the chain ID as `chain_id * 2 + 35 + y_parity`.
+            35.. => (v - 35) % 2 == 1,
 
             // If V is outside the expected range, return an error.
             _ => {
@@ -95,12 +263,11 @@ impl TransactionTrace {
             .ok_or(ProtosError::TransactionTraceMissingReceipt)
     }
 
-    fn v(&self) -> u8 {
-        if self.v.is_empty() {
-            0
-        } else {
-            self.v[0]
-        }
+    /// The `v` value of the signature as a big-endian integer, `0` when empty.
+    fn v(&self) -> u64 {
+        self.v
+            .iter()
+            .fold(0u64, |v, byte| (v << 8) | u64::from(*byte))
     }
 }
 
@@ -184,7 +351,7 @@ impl TryFrom<&TransactionTrace> for reth_primitives::TxType {
 
     fn try_from(trace: &TransactionTrace) -> Result<Self, Self::Error> {
         match Type::try_from(trace.r#type) {
-            Ok(tx_type) => Ok(TxType::from(tx_type)),
+            Ok(tx_type) => TxType::try_from(tx_type),
             Err(e) => Err(ProtosError::TxTypeConversion(e.to_string())),
         }
     }
@@ -194,65 +361,7 @@ impl TryFrom<&TransactionTrace> for Transaction {
     type Error = ProtosError;
 
     fn try_from(trace: &TransactionTrace) -> Result<Self, Self::Error> {
-        let tx_type = reth_primitives::TxType::try_from(trace)?;
-        let nonce = trace.nonce;
-        let gas_price = get_u128_or_default(&trace.gas_price)?;
-        let gas_limit = trace.gas_limit;
-        let to = TxKind::try_from(trace)?;
-        let value = Uint::from(get_u128_or_default(&trace.value)?);
-        let input = Bytes::copy_from_slice(trace.input.as_slice());
-
-        let transaction: Transaction = match tx_type {
-            TxType::Legacy => Transaction::Legacy(TxLegacy {
-                chain_id: get_legacy_chain_id(trace),
-                nonce,
-                gas_price,
-                gas_limit,
-                to,
-                value,
-                input,
-            }),
-            TxType::Eip2930 => Transaction::Eip2930(TxEip2930 {
-                chain_id: CHAIN_ID,
-                nonce,
-                gas_price,
-                gas_limit,
-                to,
-                value,
-                access_list: AccessList::try_from(trace)?,
-                input,
-            }),
-            TxType::Eip1559 => Transaction::Eip1559(TxEip1559 {
-                chain_id: CHAIN_ID,
-                nonce,
-                gas_limit,
-                max_fee_per_gas: get_u128_or_default(&trace.max_fee_per_gas)?,
-                max_priority_fee_per_gas: get_u128_or_default(&trace.max_priority_fee_per_gas)?,
-                to,
-                value,
-                access_list: AccessList::try_from(trace)?,
-                input,
-            }),
-            TxType::Eip4844 => Transaction::Eip4844(TxEip4844 {
-                chain_id: CHAIN_ID,
-                nonce,
-                gas_limit,
-                max_fee_per_gas: get_u128_or_default(&trace.max_fee_per_gas)?,
-                max_priority_fee_per_gas: get_u128_or_default(&trace.max_priority_fee_per_gas)?,
-                to: Address::try_from(trace)?,
-                value,
-                access_list: AccessList::try_from(trace)?,
-                blob_versioned_hashes: repeated_bytes_to_b256(&trace.blob_hashes)?,
-                max_fee_per_blob_gas: get_u128_or_default(&trace.blob_gas_fee_cap)?,
-                input,
-            }),
-            // The StreamingFast Ethereum Firehose block protobuf definition does not include
-            // the `access_list` entry for this type, which distinguishes it from the
-            // `Eip1559` type.
-            TxType::Eip7702 => unimplemented!(),
-        };
-
-        Ok(transaction)
+        trace.to_transaction(CHAIN_ID)
     }
 }
 
@@ -281,31 +390,7 @@ impl TryFrom<&TransactionTrace> for EthereumTxEnvelope<TxEip4844> {
     type Error = ProtosError;
 
     fn try_from(trace: &TransactionTrace) -> Result<Self, Self::Error> {
-        let tx = Transaction::try_from(trace)?;
-        let signature = Signature::try_from(trace)?;
-        let hash = FixedBytes::<32>::from_slice(trace.hash.as_slice());
-
-        let envelope = match tx {
-            Transaction::Legacy(tx) => {
-                EthereumTxEnvelope::Legacy(Signed::new_unchecked(tx, signature, hash))
-            }
-            Transaction::Eip2930(tx) => {
-                EthereumTxEnvelope::Eip2930(Signed::new_unchecked(tx, signature, hash))
-            }
-            Transaction::Eip1559(tx) => {
-                EthereumTxEnvelope::Eip1559(Signed::new_unchecked(tx, signature, hash))
-            }
-            Transaction::Eip4844(tx) => {
-                EthereumTxEnvelope::Eip4844(Signed::new_unchecked(tx, signature, hash))
-            }
-            Transaction::Eip7702(_) => {
-                return Err(ProtosError::TxTypeConversion(
-                    "Eip7702 not supported".to_string(),
-                ));
-            }
-        };
-
-        Ok(envelope)
+        trace.to_signed_with_chain_id(CHAIN_ID)
     }
 }
 
@@ -400,11 +485,38 @@ mod tests {
             ..Default::default()
         };
         // For 27 and 28, no chain ID
-        assert_eq!(get_legacy_chain_id(&trace), None);
+        assert_eq!(get_legacy_chain_id(&trace, CHAIN_ID), None);
 
         trace.v = vec![37];
         // Check chain ID for other cases
-        assert_eq!(get_legacy_chain_id(&trace), Some(CHAIN_ID));
+        assert_eq!(get_legacy_chain_id(&trace, CHAIN_ID), Some(CHAIN_ID));
+    }
+
+    #[test]
+    fn test_replay_protection() {
+        let mut trace = TransactionTrace {
+            v: vec![27],
+            ..Default::default()
+        };
+        assert!(!trace.is_replay_protected());
+        assert_eq!(trace.eip155_chain_id(), None);
+
+        trace.v = vec![37];
+        assert!(trace.is_replay_protected());
+        assert_eq!(trace.eip155_chain_id(), Some(CHAIN_ID));
+
+        trace.v = vec![45];
+        assert!(trace.is_replay_protected());
+        assert_eq!(trace.eip155_chain_id(), Some(5));
+
+        // Chain IDs above 110 need more than one byte of `v`
+        trace.v = 0x0150u16.to_be_bytes().to_vec();
+        assert_eq!(trace.eip155_chain_id(), Some(150));
+
+        trace.r#type = Type::TrxTypeDynamicFee as i32;
+        trace.v = vec![1];
+        assert!(trace.is_replay_protected());
+        assert_eq!(trace.eip155_chain_id(), None);
     }
 
     #[test]
@@ -625,6 +737,179 @@ mod tests {
         assert!(matches!(envelope, EthereumTxEnvelope::Eip4844(_)));
     }
 
+    #[test]
+    fn eip4844_transaction_conversion() {
+        use alloy_consensus::SignableTransaction;
+
+        let blob_hash = B256::repeat_byte(0x01);
+        let mut trace = TransactionTrace {
+            r#type: Type::TrxTypeBlob as i32,
+            nonce: 7,
+            max_fee_per_gas: Some(BigInt {
+                bytes: vec![0x77, 0x35, 0x94, 0x00],
+            }),
+            max_priority_fee_per_gas: Some(BigInt {
+                bytes: vec![0x3b, 0x9a, 0xca, 0x00],
+            }),
+            blob_gas_fee_cap: Some(BigInt { bytes: vec![0x03] }),
+            blob_hashes: vec![blob_hash.to_vec()],
+            gas_limit: 21000,
+            to: Address::from_slice(&[0x02; 20]).to_vec(),
+            calls: vec![Call::default()],
+            r: vec![0x01; 32],
+            s: vec![0x02; 32],
+            v: vec![1],
+            ..Default::default()
+        };
+
+        let tx = match Transaction::try_from(&trace).unwrap() {
+            Transaction::Eip4844(tx) => tx,
+            _ => panic!("Expected Eip4844 transaction"),
+        };
+        let expected = TxEip4844 {
+            chain_id: CHAIN_ID,
+            nonce: 7,
+            gas_limit: 21000,
+            max_fee_per_gas: 2_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: Address::from_slice(&[0x02; 20]),
+            value: U256::ZERO,
+            access_list: AccessList::default(),
+            blob_versioned_hashes: vec![blob_hash],
+            max_fee_per_blob_gas: 3,
+            input: Bytes::new(),
+        };
+        assert_eq!(tx, expected);
+
+        // keccak256(0x03 || rlp([chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas,
+        // gas_limit, to, value, data, access_list, max_fee_per_blob_gas, blob_versioned_hashes,
+        // y_parity, r, s])), computed outside of alloy for the fields above.
+        let expected_hash: B256 =
+            "0x1f1109ca5c22637a813b9c07903e5203f8fcbb721f4431330b93c95eea0109cf"
+                .parse()
+                .unwrap();
+        let signature = Signature::try_from(&trace).unwrap();
+        assert_eq!(*tx.into_signed(signature).hash(), expected_hash);
+
+        trace.hash = expected_hash.to_vec();
+        let envelope = EthereumTxEnvelope::<TxEip4844>::try_from(&trace).unwrap();
+        assert_eq!(
+            envelope,
+            EthereumTxEnvelope::Eip4844(expected.into_signed(signature))
+        );
+    }
+
+    #[test]
+    fn eip1559_envelope_with_chain_id() {
+        const ARBITRUM_ONE_CHAIN_ID: ChainId = 42161;
+
+        let trace = TransactionTrace {
+            r#type: Type::TrxTypeDynamicFee as i32,
+            nonce: 1,
+            max_fee_per_gas: Some(BigInt {
+                bytes: vec![0, 0, 1],
+            }),
+            max_priority_fee_per_gas: Some(BigInt { bytes: vec![0, 1] }),
+            gas_limit: 21000,
+            to: Address::from_slice(&[0x02; 20]).to_vec(),
+            calls: vec![Call::default()],
+            r: vec![0x01; 32],
+            s: vec![0x02; 32],
+            v: vec![1],
+            hash: vec![0; 32],
+            ..Default::default()
+        };
+
+        match trace
+            .to_signed_with_chain_id(ARBITRUM_ONE_CHAIN_ID)
+            .unwrap()
+        {
+            EthereumTxEnvelope::Eip1559(signed) => {
+                assert_eq!(signed.tx().chain_id, ARBITRUM_ONE_CHAIN_ID)
+            }
+            _ => panic!("Expected Eip1559 envelope"),
+        }
+
+        // The `TryFrom` conversion keeps assuming mainnet.
+        match EthereumTxEnvelope::<TxEip4844>::try_from(&trace).unwrap() {
+            EthereumTxEnvelope::Eip1559(signed) => assert_eq!(signed.tx().chain_id, CHAIN_ID),
+            _ => panic!("Expected Eip1559 envelope"),
+        }
+    }
+
+    /// The example transaction from [EIP-155](https://eips.ethereum.org/EIPS/eip-155),
+    /// signed with the private key `0x4646..46`.
+    fn eip155_example_trace() -> TransactionTrace {
+        TransactionTrace {
+            r#type: Type::TrxTypeLegacy as i32,
+            nonce: 9,
+            gas_price: Some(BigInt {
+                bytes: hex::decode("04a817c800").unwrap(),
+            }),
+            gas_limit: 21000,
+            to: Address::from_slice(&[0x35; 20]).to_vec(),
+            value: Some(BigInt {
+                bytes: hex::decode("0de0b6b3a7640000").unwrap(),
+            }),
+            calls: vec![Call::default()],
+            r: hex::decode("28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276")
+                .unwrap(),
+            s: hex::decode("67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83")
+                .unwrap(),
+            v: vec![37],
+            hash: hex::decode("33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788")
+                .unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_recover_sender() {
+        let sender: Address = "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"
+            .parse()
+            .unwrap();
+
+        let mut trace = eip155_example_trace();
+        assert_eq!(trace.recover_sender().unwrap(), sender);
+
+        trace.from = sender.to_vec();
+        assert_eq!(trace.recover_sender().unwrap(), sender);
+
+        trace.from = vec![0x01; 20];
+        assert!(matches!(
+            trace.recover_sender(),
+            Err(ProtosError::SenderMismatch { recovered, .. }) if recovered == sender
+        ));
+    }
+
+    #[test]
+    fn test_recover_sender_on_other_chain() {
+        let sender: Address = "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"
+            .parse()
+            .unwrap();
+
+        // The EIP-155 example transaction, signed with the same key for chain ID 5.
+        let trace = TransactionTrace {
+            r: hex::decode("f973a0b87062c389d125d8199e803b832b6ac6bf7867a4f6cd87506060fc4c58")
+                .unwrap(),
+            s: hex::decode("36b8b8d94ba7e12ceaff46d954f8a4115600a7a20c805d0e30e3ea52d88bec68")
+                .unwrap(),
+            v: vec![45],
+            hash: hex::decode("a966bfbf02699e1db6275fab711c1f940b83eb32cf5571a80e8444adc1c434c9")
+                .unwrap(),
+            ..eip155_example_trace()
+        };
+        assert_eq!(trace.eip155_chain_id(), Some(5));
+        assert_eq!(trace.recover_sender().unwrap(), sender);
+        assert_eq!(trace.recover_sender_with_chain_id(5).unwrap(), sender);
+
+        // Rebuilt for mainnet, the signature hash differs, and so does the recovered signer.
+        assert_ne!(
+            trace.recover_sender_with_chain_id(CHAIN_ID).ok(),
+            Some(sender)
+        );
+    }
+
     #[test]
     fn envelope_conversion_preserves_tx_type() {
         let test_cases = vec![
@@ -705,5 +990,14 @@ mod tests {
             reth_primitives::TxType::try_from(&eip4844_trace).unwrap(),
             reth_primitives::TxType::Eip4844
         );
+
+        let optimism_deposit_trace = TransactionTrace {
+            r#type: Type::TrxTypeOptimismDeposit as i32,
+            ..Default::default()
+        };
+        assert!(matches!(
+            reth_primitives::TxType::try_from(&optimism_deposit_trace),
+            Err(ProtosError::UnsupportedTxType(_))
+        ));
     }
 }
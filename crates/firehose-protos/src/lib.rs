@@ -17,7 +17,9 @@ mod bstream {
 pub use bstream::v1::Block as BstreamBlock;
 pub use error::ProtosError;
 pub use ethereum_v2::{
-    eth_block::FullReceipt, BigInt, Block as EthBlock, BlockHeader, Uint64NestedArray,
+    eth_block::{FullReceipt, HexBlockHeader, VerificationCoverage},
+    log::{Erc20Transfer, ERC20_TRANSFER_TOPIC},
+    BigInt, Block as EthBlock, BlockHeader, Uint64NestedArray,
 };
 pub use prost_wkt_types::Timestamp;
 pub use solana::Block as SolBlock;
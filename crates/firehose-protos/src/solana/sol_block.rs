@@ -5,6 +5,7 @@ use super::Block;
 use firehose_rs::{Response, SingleBlockResponse};
 use prost::Message;
 use prost_wkt_types::Any;
+use tracing::error;
 
 use crate::error::ProtosError;
 
@@ -49,3 +50,161 @@ impl TryFrom<Response> for Block {
         decode_block(response)
     }
 }
+
+impl Block {
+    /// Returns the number of transactions in the block.
+    pub fn transaction_count(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Returns the number of transactions in the block that executed without error.
+    pub fn successful_transaction_count(&self) -> usize {
+        self.transactions
+            .iter()
+            .filter(|confirmed| {
+                confirmed
+                    .meta
+                    .as_ref()
+                    .is_some_and(|meta| meta.err.is_none())
+            })
+            .count()
+    }
+
+    /// Returns the total compute units consumed by the block's transactions.
+    ///
+    /// Transactions whose meta does not report consumed compute units count as zero.
+    pub fn total_compute_units_consumed(&self) -> u64 {
+        self.transactions
+            .iter()
+            .filter_map(|confirmed| confirmed.meta.as_ref())
+            .filter_map(|meta| meta.compute_units_consumed)
+            .sum()
+    }
+
+    /// Checks the internal consistency of the block. Returns `true` if it is consistent,
+    /// `false` otherwise.
+    ///
+    /// Unlike an Ethereum block, the block hash cannot be recomputed from the contents of a
+    /// Solana block, so this checks that:
+    /// - the block height and the block hashes are present,
+    /// - the parent slot precedes the block's slot,
+    /// - every transaction has its status meta, and carries as many signatures as its
+    ///   message header requires.
+    pub fn sol_block_is_verified(&self) -> bool {
+        match self.verify_consistency() {
+            Ok(()) => true,
+            Err(e) => {
+                error!("Failed to verify Solana block at slot {}: {e}", self.slot);
+                false
+            }
+        }
+    }
+
+    fn verify_consistency(&self) -> Result<(), ProtosError> {
+        let inconsistent = |reason: String| ProtosError::SolBlockInconsistent(reason);
+
+        if self.block_height.is_none() {
+            return Err(inconsistent("missing block height".to_string()));
+        }
+        if self.blockhash.is_empty() || self.previous_blockhash.is_empty() {
+            return Err(inconsistent("missing block hash".to_string()));
+        }
+        if self.slot != 0 && self.parent_slot >= self.slot {
+            return Err(inconsistent(format!(
+                "parent slot {} does not precede slot {}",
+                self.parent_slot, self.slot
+            )));
+        }
+
+        for (index, confirmed) in self.transactions.iter().enumerate() {
+            let transaction = confirmed
+                .transaction
+                .as_ref()
+                .ok_or_else(|| inconsistent(format!("transaction {index} is missing")))?;
+            if confirmed.meta.is_none() {
+                return Err(inconsistent(format!(
+                    "transaction {index} is missing its status meta"
+                )));
+            }
+
+            let required_signatures = transaction
+                .message
+                .as_ref()
+                .and_then(|message| message.header.as_ref())
+                .map(|header| header.num_required_signatures as usize)
+                .ok_or_else(|| {
+                    inconsistent(format!("transaction {index} is missing its message header"))
+                })?;
+            if transaction.signatures.len() != required_signatures {
+                return Err(inconsistent(format!(
+                    "transaction {index} has {} signatures, {required_signatures} required",
+                    transaction.signatures.len()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solana::{
+        BlockHeight, ConfirmedTransaction, Message, MessageHeader, Transaction,
+        TransactionStatusMeta,
+    };
+
+    use super::*;
+
+    fn consistent_block() -> Block {
+        Block {
+            previous_blockhash: "4XnrnSBHnA".to_string(),
+            blockhash: "8NQ2DstBY2".to_string(),
+            parent_slot: 325942299,
+            slot: 325942300,
+            block_height: Some(BlockHeight {
+                block_height: 304202974,
+            }),
+            transactions: vec![ConfirmedTransaction {
+                transaction: Some(Transaction {
+                    signatures: vec![vec![1; 64]],
+                    message: Some(Message {
+                        header: Some(MessageHeader {
+                            num_required_signatures: 1,
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                }),
+                meta: Some(TransactionStatusMeta::default()),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sol_block_is_verified() {
+        assert!(consistent_block().sol_block_is_verified());
+
+        let mut block = consistent_block();
+        block.block_height = None;
+        assert!(!block.sol_block_is_verified());
+
+        let mut block = consistent_block();
+        block.parent_slot = block.slot;
+        assert!(!block.sol_block_is_verified());
+
+        let mut block = consistent_block();
+        block.transactions[0].meta = None;
+        assert!(!block.sol_block_is_verified());
+
+        let mut block = consistent_block();
+        block.transactions[0]
+            .transaction
+            .as_mut()
+            .unwrap()
+            .signatures
+            .push(vec![2; 64]);
+        assert!(!block.sol_block_is_verified());
+    }
+}